@@ -9,6 +9,7 @@ use bevy::{
     ui::ui_layout_system,
 };
 use bevy_editor_camera::{EditorCamera2d, EditorCamera2dPlugin};
+use bevy_editor_core::EditorEntity;
 use bevy_editor_styles::Theme;
 use bevy_infinite_grid::{InfiniteGrid, InfiniteGridPlugin, InfiniteGridSettings};
 use bevy_pane_layout::{PaneContentNode, PaneRegistry};
@@ -76,6 +77,7 @@ fn setup(mut commands: Commands, theme: Res<Theme>) {
         },
         Transform::from_rotation(Quat::from_rotation_arc(Vec3::Y, Vec3::Z)),
         RenderLayers::layer(2),
+        EditorEntity,
     ));
 }
 
@@ -132,6 +134,7 @@ fn on_pane_creation(
                 ..default()
             },
             RenderLayers::from_layers(&[0, 2]),
+            EditorEntity,
         ))
         .id();
 
@@ -12,22 +12,111 @@ use bevy::{
     },
     ui::ui_layout_system,
 };
-use bevy_editor_cam::prelude::{DefaultEditorCamPlugins, EditorCam};
+use bevy_editor_cam::prelude::{DefaultEditorCamPlugins, EditorCam, EnabledMotion};
+use bevy_editor_core::EditorEntity;
 use bevy_editor_styles::Theme;
 use bevy_infinite_grid::{InfiniteGrid, InfiniteGridPlugin, InfiniteGridSettings};
 use bevy_pane_layout::{PaneContentNode, PaneRegistry};
+use bevy_transform_gizmos::GIZMO_RENDER_LAYER;
+use selection::ViewportSelectionPlugin;
+
+mod outline;
+mod selection;
+
+/// Distance from the origin of the world to the cameras of the orthographic views.
+const ORTHOGRAPHIC_CAMERA_DISTANCE: f32 = 100.0;
 
 /// The identifier for the 3D Viewport.
 /// This is present on any pane that is a 3D Viewport.
 #[derive(Component)]
 pub struct Bevy3dViewport {
     camera: Entity,
+    view: ViewportView,
 }
 
-impl Default for Bevy3dViewport {
-    fn default() -> Self {
+impl Bevy3dViewport {
+    /// A viewport looking at the world from `view`.
+    pub fn new(view: ViewportView) -> Self {
         Bevy3dViewport {
             camera: Entity::PLACEHOLDER,
+            view,
+        }
+    }
+
+    /// The camera rendering this viewport.
+    pub fn camera(&self) -> Entity {
+        self.camera
+    }
+
+    /// Where this viewport looks at the world from.
+    pub fn view(&self) -> ViewportView {
+        self.view
+    }
+}
+
+impl Default for Bevy3dViewport {
+    fn default() -> Self {
+        Bevy3dViewport::new(ViewportView::Perspective)
+    }
+}
+
+/// Where a 3D viewport looks at the world from.
+///
+/// The perspective view can be orbited, the orthographic views can only be panned and zoomed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ViewportView {
+    /// A free perspective camera.
+    Perspective,
+    /// An orthographic camera looking down the Y axis.
+    Top,
+    /// An orthographic camera looking down the Z axis.
+    Front,
+    /// An orthographic camera looking down the X axis.
+    Side,
+}
+
+impl ViewportView {
+    /// All the views, in the order their panes are registered.
+    pub const ALL: [ViewportView; 4] = [
+        ViewportView::Perspective,
+        ViewportView::Top,
+        ViewportView::Front,
+        ViewportView::Side,
+    ];
+
+    /// The name of the pane showing this view.
+    pub fn pane_name(self) -> &'static str {
+        match self {
+            ViewportView::Perspective => "Viewport 3D",
+            ViewportView::Top => "Viewport 3D Top",
+            ViewportView::Front => "Viewport 3D Front",
+            ViewportView::Side => "Viewport 3D Side",
+        }
+    }
+
+    /// Whether the view uses an orthographic projection.
+    pub fn is_orthographic(self) -> bool {
+        self != ViewportView::Perspective
+    }
+
+    /// The transform of the camera when the view is created.
+    fn initial_camera_transform(self) -> Transform {
+        match self {
+            ViewportView::Perspective => {
+                Transform::from_translation(Vec3::ONE * 5.).looking_at(Vec3::ZERO, Vec3::Y)
+            }
+            ViewportView::Top => {
+                Transform::from_translation(Vec3::Y * ORTHOGRAPHIC_CAMERA_DISTANCE)
+                    .looking_at(Vec3::ZERO, Vec3::NEG_Z)
+            }
+            ViewportView::Front => {
+                Transform::from_translation(Vec3::Z * ORTHOGRAPHIC_CAMERA_DISTANCE)
+                    .looking_at(Vec3::ZERO, Vec3::Y)
+            }
+            ViewportView::Side => {
+                Transform::from_translation(Vec3::X * ORTHOGRAPHIC_CAMERA_DISTANCE)
+                    .looking_at(Vec3::ZERO, Vec3::Y)
+            }
         }
     }
 }
@@ -40,7 +129,7 @@ impl Plugin for Viewport3dPanePlugin {
         if !app.is_plugin_added::<InfiniteGridPlugin>() {
             app.add_plugins(InfiniteGridPlugin);
         }
-        app.add_plugins(DefaultEditorCamPlugins)
+        app.add_plugins((DefaultEditorCamPlugins, ViewportSelectionPlugin))
             .add_systems(Startup, setup)
             .add_systems(
                 PreUpdate,
@@ -62,11 +151,12 @@ impl Plugin for Viewport3dPanePlugin {
                 },
             );
 
-        app.world_mut()
-            .get_resource_or_init::<PaneRegistry>()
-            .register("Viewport 3D", |mut commands, pane_root| {
-                commands.entity(pane_root).insert(Bevy3dViewport::default());
+        let mut pane_registry = app.world_mut().get_resource_or_init::<PaneRegistry>();
+        for view in ViewportView::ALL {
+            pane_registry.register(view.pane_name(), move |mut commands, pane_root| {
+                commands.entity(pane_root).insert(Bevy3dViewport::new(view));
             });
+        }
     }
 }
 
@@ -138,6 +228,7 @@ fn setup(mut commands: Commands, theme: Res<Theme>) {
             ..default()
         },
         RenderLayers::layer(1),
+        EditorEntity,
     ));
 }
 
@@ -186,6 +277,29 @@ fn on_pane_creation(
             commands.entity(trigger.entity()).remove::<Active>();
         });
 
+    let mut viewport = query.get_mut(pane_root).unwrap();
+    let view = viewport.view;
+    let (projection, editor_cam) = if view.is_orthographic() {
+        (
+            Projection::Orthographic(OrthographicProjection {
+                scale: 0.01,
+                ..OrthographicProjection::default_3d()
+            }),
+            EditorCam {
+                // Orbiting would leave the axis the view is aligned with. The anchor depth sets the
+                // scale and the clipping planes of the orthographic projection.
+                enabled_motion: EnabledMotion {
+                    orbit: false,
+                    ..default()
+                },
+                ..EditorCam::default()
+                    .with_initial_anchor_depth(ORTHOGRAPHIC_CAMERA_DISTANCE as f64)
+            },
+        )
+    } else {
+        (Projection::default(), EditorCam::default())
+    };
+
     let camera_id = commands
         .spawn((
             Camera3d::default(),
@@ -194,13 +308,15 @@ fn on_pane_creation(
                 clear_color: ClearColorConfig::Custom(theme.viewport.background_color),
                 ..default()
             },
-            EditorCam::default(),
-            Transform::from_translation(Vec3::ONE * 5.).looking_at(Vec3::ZERO, Vec3::Y),
-            RenderLayers::from_layers(&[0, 1]),
+            projection,
+            editor_cam,
+            view.initial_camera_transform(),
+            RenderLayers::from_layers(&[0, 1, GIZMO_RENDER_LAYER]),
+            EditorEntity,
         ))
         .id();
 
-    query.get_mut(pane_root).unwrap().camera = camera_id;
+    viewport.camera = camera_id;
 }
 
 fn update_render_target_size(
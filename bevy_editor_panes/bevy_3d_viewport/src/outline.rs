@@ -0,0 +1,181 @@
+//! Outlining the selected meshes along their silhouette.
+//!
+//! Each selected mesh gets a copy drawn with the [`OutlineMaterial`], an inverted hull: only its
+//! back faces are drawn, pushed out along their normals, so they show around the mesh.
+
+use bevy::{
+    asset::embedded_asset,
+    ecs::entity::EntityHashMap,
+    pbr::{MaterialPipeline, MaterialPipelineKey, NotShadowCaster},
+    prelude::*,
+    render::{
+        mesh::MeshVertexBufferLayoutRef,
+        render_resource::{
+            AsBindGroup, Face, RenderPipelineDescriptor, ShaderRef, SpecializedMeshPipelineError,
+        },
+        view::{NoFrustumCulling, RenderLayers, VisibilitySystems},
+    },
+};
+use bevy_editor_core::{EditorEntity, EditorSelection};
+
+/// Path of the outline shader, embedded in the crate.
+const OUTLINE_SHADER_PATH: &str = "embedded://bevy_3d_viewport/outline.wgsl";
+
+/// Color of the outline of the selected meshes.
+const SELECTION_COLOR: Color = Color::srgb(1.0, 0.6, 0.1);
+
+/// Width of the outline of the selected meshes, in pixels of the viewport.
+const SELECTION_OUTLINE_WIDTH: f32 = 3.0;
+
+pub(crate) struct SelectionOutlinePlugin;
+
+impl Plugin for SelectionOutlinePlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "outline.wgsl");
+
+        app.add_plugins(MaterialPlugin::<OutlineMaterial> {
+            prepass_enabled: false,
+            shadows_enabled: false,
+            ..default()
+        })
+        .init_resource::<SelectionOutlines>()
+        .add_systems(Startup, setup_outline_material)
+        .add_systems(
+            PostUpdate,
+            sync_selection_outlines
+                .after(TransformSystem::TransformPropagate)
+                .after(VisibilitySystems::VisibilityPropagate)
+                .before(VisibilitySystems::CheckVisibility),
+        );
+    }
+}
+
+/// The material of the outlines, drawing the back faces of a mesh pushed out along their normals.
+#[derive(Asset, TypePath, AsBindGroup, Clone)]
+pub(crate) struct OutlineMaterial {
+    /// Color of the outline.
+    #[uniform(0)]
+    color: LinearRgba,
+    /// Width of the outline, in pixels.
+    #[uniform(0)]
+    width: f32,
+}
+
+impl Material for OutlineMaterial {
+    fn vertex_shader() -> ShaderRef {
+        OUTLINE_SHADER_PATH.into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        OUTLINE_SHADER_PATH.into()
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayoutRef,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        let vertex_layout = layout.0.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            Mesh::ATTRIBUTE_NORMAL.at_shader_location(1),
+        ])?;
+        descriptor.vertex.buffers = vec![vertex_layout];
+        // Only the back faces show around the mesh
+        descriptor.primitive.cull_mode = Some(Face::Front);
+        Ok(())
+    }
+}
+
+/// The material shared by the outlines of the selected meshes.
+#[derive(Resource)]
+struct SelectionOutlineMaterial(Handle<OutlineMaterial>);
+
+/// The outline entity of each outlined mesh.
+#[derive(Resource, Default)]
+struct SelectionOutlines(EntityHashMap<Entity>);
+
+fn setup_outline_material(mut commands: Commands, mut materials: ResMut<Assets<OutlineMaterial>>) {
+    commands.insert_resource(SelectionOutlineMaterial(materials.add(OutlineMaterial {
+        color: SELECTION_COLOR.into(),
+        width: SELECTION_OUTLINE_WIDTH,
+    })));
+}
+
+/// Outline the selected meshes, and the meshes under the other selected entities.
+///
+/// The outlines are root entities that copy the mesh and the global transform of their mesh, so
+/// they follow it without being part of the edited hierarchy.
+#[allow(clippy::type_complexity)]
+fn sync_selection_outlines(
+    mut commands: Commands,
+    mut outlines: ResMut<SelectionOutlines>,
+    material: Option<Res<SelectionOutlineMaterial>>,
+    meshes: Res<Assets<Mesh>>,
+    selection: Res<EditorSelection>,
+    children_query: Query<&Children>,
+    mesh_query: Query<
+        (
+            &Mesh3d,
+            &GlobalTransform,
+            &InheritedVisibility,
+            Option<&RenderLayers>,
+        ),
+        Without<EditorEntity>,
+    >,
+) {
+    let Some(material) = material else {
+        return;
+    };
+
+    let mut outlined = EntityHashMap::default();
+    for selected in selection.iter() {
+        for entity in std::iter::once(selected).chain(children_query.iter_descendants(selected)) {
+            let Ok((mesh, transform, visibility, render_layers)) = mesh_query.get(entity) else {
+                continue;
+            };
+            // The hull is pushed out along the normals
+            if !meshes
+                .get(&mesh.0)
+                .is_some_and(|mesh| mesh.attribute(Mesh::ATTRIBUTE_NORMAL).is_some())
+            {
+                continue;
+            }
+            if outlined.contains_key(&entity) {
+                continue;
+            }
+
+            let outline = outlines.0.remove(&entity).unwrap_or_else(|| {
+                commands
+                    .spawn((
+                        Name::new("Selection Outline"),
+                        EditorEntity,
+                        MeshMaterial3d(material.0.clone()),
+                        NotShadowCaster,
+                        NoFrustumCulling,
+                        PickingBehavior::IGNORE,
+                    ))
+                    .id()
+            });
+            commands.entity(outline).insert((
+                mesh.clone(),
+                transform.compute_transform(),
+                // Set directly, the propagation already ran this frame
+                *transform,
+                if visibility.get() {
+                    Visibility::Inherited
+                } else {
+                    Visibility::Hidden
+                },
+                render_layers.cloned().unwrap_or_default(),
+            ));
+            outlined.insert(entity, outline);
+        }
+    }
+
+    // The meshes that aren't selected anymore
+    for outline in outlines.0.values() {
+        commands.entity(*outline).despawn();
+    }
+    outlines.0 = outlined;
+}
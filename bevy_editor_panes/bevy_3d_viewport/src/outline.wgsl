@@ -0,0 +1,50 @@
+// Inverted hull outline of the selected meshes.
+//
+// The back faces of the mesh are pushed out along their normal by a fixed number of pixels, so the
+// outline stays the same width whatever the distance to the camera. The faces of the mesh itself
+// hide the rest of the hull, only the part past its silhouette is visible.
+
+#import bevy_pbr::{
+    mesh_functions,
+    mesh_view_bindings::view,
+    view_transformations::position_world_to_clip,
+}
+
+struct OutlineMaterial {
+    color: vec4<f32>,
+    width: f32,
+}
+
+@group(2) @binding(0) var<uniform> material: OutlineMaterial;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+}
+
+@vertex
+fn vertex(vertex: Vertex) -> @builtin(position) vec4<f32> {
+    let world_from_local = mesh_functions::get_world_from_local(vertex.instance_index);
+    let world_position = mesh_functions::mesh_position_local_to_world(
+        world_from_local,
+        vec4<f32>(vertex.position, 1.0),
+    );
+    let world_normal = mesh_functions::mesh_normal_local_to_world(
+        vertex.normal,
+        vertex.instance_index,
+    );
+
+    var clip_position = position_world_to_clip(world_position.xyz);
+    let clip_normal = (view.clip_from_world * vec4<f32>(world_normal, 0.0)).xy;
+    let direction = clip_normal / max(length(clip_normal), 1e-6);
+    // Clip space spans 2 units across the viewport, and is divided by w afterwards
+    let offset = direction * material.width * 2.0 / view.viewport.zw * clip_position.w;
+    clip_position += vec4<f32>(offset, 0.0, 0.0);
+    return clip_position;
+}
+
+@fragment
+fn fragment() -> @location(0) vec4<f32> {
+    return material.color;
+}
@@ -0,0 +1,299 @@
+//! Selecting entities from the 3D viewport.
+//!
+//! Clicking a mesh selects it, Ctrl toggles it and Shift adds it to the selection. Clicking
+//! nothing deselects everything. Dragging while holding Shift adds every mesh whose bounding box
+//! is at least partly in the dragged box to the selection. The selected meshes are outlined by the
+//! [`outline`](crate::outline) module.
+
+use bevy::{
+    ecs::system::SystemParam,
+    picking::{
+        pointer::{PointerId, PointerLocation},
+        PickSet,
+    },
+    prelude::*,
+    render::primitives::Aabb,
+    window::PrimaryWindow,
+};
+use bevy_editor_cam::input::default_camera_inputs;
+use bevy_editor_core::{
+    raycast::{pointer_ray, ray_aabb_distance, ray_mesh_distance, viewport_position},
+    EditorEntity, EditorSelection, Locked, PointerCapture,
+};
+use bevy_editor_styles::Theme;
+use bevy_pane_layout::PaneContentNode;
+use bevy_transform_gizmos::TransformGizmoSet;
+
+use crate::{outline::SelectionOutlinePlugin, Bevy3dViewport};
+
+/// How far the pointer can move between the press and the release of a click, in logical pixels.
+const CLICK_MAX_DISTANCE: f32 = 4.0;
+
+pub(crate) struct ViewportSelectionPlugin;
+
+impl Plugin for ViewportSelectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(SelectionOutlinePlugin)
+            .init_resource::<SelectionPress>()
+            .add_systems(
+                PreUpdate,
+                select_in_viewport
+                    .after(PickSet::Last)
+                    .after(TransformGizmoSet)
+                    // Box selection must not also move the camera
+                    .before(default_camera_inputs),
+            );
+    }
+}
+
+/// The mouse press that started in a viewport, until it is released.
+#[derive(Resource, Default)]
+struct SelectionPress(Option<Press>);
+
+struct Press {
+    camera: Entity,
+    start: Vec2,
+    current: Vec2,
+    /// The node showing the dragged box, if this is a box selection.
+    box_node: Option<Entity>,
+}
+
+/// The meshes that can be selected from the viewport.
+#[derive(SystemParam)]
+struct SelectableMeshes<'w, 's> {
+    meshes: Res<'w, Assets<Mesh>>,
+    mesh_query: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Mesh3d,
+            &'static Aabb,
+            &'static GlobalTransform,
+            &'static ViewVisibility,
+        ),
+        Without<EditorEntity>,
+    >,
+    locked_query: Query<'w, 's, (), With<Locked>>,
+    parent_query: Query<'w, 's, &'static Parent>,
+}
+
+impl SelectableMeshes<'_, '_> {
+    fn iter(&self) -> impl Iterator<Item = (Entity, &Mesh3d, &Aabb, &GlobalTransform)> + '_ {
+        self.mesh_query
+            .iter()
+            .filter(|(entity, .., visibility)| {
+                visibility.get()
+                    && !self.locked_query.contains(*entity)
+                    && !self
+                        .parent_query
+                        .iter_ancestors(*entity)
+                        .any(|ancestor| self.locked_query.contains(ancestor))
+            })
+            .map(|(entity, mesh, aabb, transform, _)| (entity, mesh, aabb, transform))
+    }
+
+    /// The closest mesh hit by `ray`.
+    ///
+    /// The bounding boxes are tested first, then the triangles of the meshes they hit.
+    fn pick(&self, ray: Ray3d) -> Option<Entity> {
+        let mut candidates: Vec<_> = self
+            .iter()
+            .filter_map(|(entity, mesh, aabb, transform)| {
+                Some((
+                    entity,
+                    ray_aabb_distance(ray, aabb, transform)?,
+                    mesh,
+                    transform,
+                ))
+            })
+            .collect();
+        candidates.sort_by(|(_, a, ..), (_, b, ..)| a.total_cmp(b));
+
+        let mut closest: Option<(Entity, f32)> = None;
+        for (entity, aabb_distance, mesh, transform) in candidates {
+            // A mesh can't be closer than its bounding box
+            if closest.is_some_and(|(_, distance)| distance <= aabb_distance) {
+                break;
+            }
+            let distance = match self
+                .meshes
+                .get(&mesh.0)
+                .and_then(|mesh| ray_mesh_distance(ray, mesh, transform))
+            {
+                Some(Some(distance)) => distance,
+                // The ray goes through the bounding box but misses the triangles
+                Some(None) => continue,
+                None => aabb_distance,
+            };
+            if !closest.is_some_and(|(_, closest)| closest <= distance) {
+                closest = Some((entity, distance));
+            }
+        }
+        closest.map(|(entity, _)| entity)
+    }
+
+    /// The meshes whose bounding box overlaps `rect`, in viewport coordinates.
+    ///
+    /// The bounding box is projected as the rectangle around its corners in front of the camera.
+    fn in_rect(
+        &self,
+        camera: &Camera,
+        camera_transform: &GlobalTransform,
+        rect: Rect,
+    ) -> Vec<Entity> {
+        self.iter()
+            .filter(|(_, _, aabb, transform)| {
+                let (center, half_extents) = (Vec3::from(aabb.center), aabb.half_extents.into());
+                let projected = (0..8)
+                    .filter_map(|corner| {
+                        let sign = Vec3::new(
+                            if corner & 1 == 0 { -1.0 } else { 1.0 },
+                            if corner & 2 == 0 { -1.0 } else { 1.0 },
+                            if corner & 4 == 0 { -1.0 } else { 1.0 },
+                        );
+                        let corner = transform.transform_point(center + sign * half_extents);
+                        viewport_position(camera, camera_transform, corner)
+                    })
+                    .map(|position| Rect::from_corners(position, position))
+                    .reduce(|bounds, corner| bounds.union(corner));
+                projected.is_some_and(|bounds| {
+                    bounds.min.cmple(rect.max).all() && bounds.max.cmpge(rect.min).all()
+                })
+            })
+            .map(|(entity, ..)| entity)
+            .collect()
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn select_in_viewport(
+    mut commands: Commands,
+    mut press: ResMut<SelectionPress>,
+    mut selection: ResMut<EditorSelection>,
+    mut pointer_capture: ResMut<PointerCapture>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    theme: Res<Theme>,
+    pointers: Query<(&PointerId, &PointerLocation)>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    viewports: Query<(Entity, &Bevy3dViewport)>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    children_query: Query<&Children>,
+    content: Query<&PaneContentNode>,
+    selectable: SelectableMeshes,
+) {
+    let location = pointers
+        .iter()
+        .find_map(|(id, location)| (*id == PointerId::Mouse).then(|| location.location())?);
+    let ctrl = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
+    if let Some(current_press) = &mut press.0 {
+        let Ok((camera, camera_transform)) = cameras.get(current_press.camera) else {
+            if let Some(box_node) = press.0.take().and_then(|press| press.box_node) {
+                commands.entity(box_node).despawn_recursive();
+                pointer_capture.release();
+            }
+            return;
+        };
+        if let Some(location) =
+            location.filter(|location| location.is_in_viewport(camera, &primary_window))
+        {
+            current_press.current = location.position;
+        }
+        if let Some(box_node) = current_press.box_node {
+            commands.entity(box_node).insert(box_selection_node(
+                current_press.start,
+                current_press.current,
+            ));
+        }
+        if !mouse.just_released(MouseButton::Left) {
+            return;
+        }
+
+        let Some(released) = press.0.take() else {
+            return;
+        };
+        if let Some(box_node) = released.box_node {
+            commands.entity(box_node).despawn_recursive();
+            pointer_capture.release();
+        }
+
+        if released.start.distance(released.current) <= CLICK_MAX_DISTANCE {
+            let hit = pointer_ray(camera, camera_transform, released.current)
+                .and_then(|ray| selectable.pick(ray));
+            match hit {
+                Some(entity) if ctrl => selection.toggle(entity),
+                Some(entity) if shift => selection.add(entity),
+                Some(entity) => selection.set(entity),
+                None if !ctrl && !shift => selection.clear(),
+                None => {}
+            }
+        } else if released.box_node.is_some() {
+            let rect = Rect::from_corners(released.start, released.current);
+            selection.extend(selectable.in_rect(camera, camera_transform, rect));
+        }
+        // Otherwise the press was used to move the camera
+        return;
+    }
+
+    // A press grabbing a gizmo handle is captured by the gizmo
+    if !mouse.just_pressed(MouseButton::Left) || pointer_capture.is_captured() {
+        return;
+    }
+    let Some(location) = location else {
+        return;
+    };
+    let Some((pane_root, viewport)) = viewports.iter().find(|(_, viewport)| {
+        cameras
+            .get(viewport.camera)
+            .is_ok_and(|(camera, _)| location.is_in_viewport(camera, &primary_window))
+    }) else {
+        return;
+    };
+
+    let box_node = if shift {
+        children_query
+            .iter_descendants(pane_root)
+            .find(|e| content.contains(*e))
+            .map(|content_node| {
+                commands
+                    .spawn((
+                        box_selection_node(location.position, location.position),
+                        BorderColor(theme.text.text_color),
+                        BackgroundColor(theme.text.text_color.with_alpha(0.1)),
+                        PickingBehavior::IGNORE,
+                    ))
+                    .set_parent(content_node)
+                    .id()
+            })
+    } else {
+        None
+    };
+
+    if box_node.is_some() {
+        // Drag a selection box instead of panning the camera
+        pointer_capture.capture();
+    }
+    press.0 = Some(Press {
+        camera: viewport.camera,
+        start: location.position,
+        current: location.position,
+        box_node,
+    });
+}
+
+/// The node of the selection box between `start` and `end`, in viewport coordinates.
+fn box_selection_node(start: Vec2, end: Vec2) -> Node {
+    let rect = Rect::from_corners(start, end);
+    Node {
+        position_type: PositionType::Absolute,
+        left: Val::Px(rect.min.x),
+        top: Val::Px(rect.min.y),
+        width: Val::Px(rect.width()),
+        height: Val::Px(rect.height()),
+        border: UiRect::all(Val::Px(1.0)),
+        ..default()
+    }
+}
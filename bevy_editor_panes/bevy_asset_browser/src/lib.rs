@@ -1,13 +1,13 @@
 //! A UI element for browsing assets in the Bevy Editor.
 /// The intent of this system is to provide a simple and frictionless way to browse assets in the Bevy Editor.
 /// The asset browser is a replica of the your asset directory on disk and get's automatically updated when the directory is modified.
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
 use bevy::{
     asset::{
         embedded_asset,
         io::{file::FileAssetReader, AssetSourceId},
-        AssetPlugin,
+        AssetPath, AssetPlugin,
     },
     prelude::*,
 };
@@ -48,10 +48,16 @@ impl Plugin for AssetBrowserPanePlugin {
             absolute_path
         };
 
-        app.add_plugins(ScrollBoxPlugin)
-            .insert_resource(DefaultSourceFilePath(default_source_absolute_file_path))
+        if !app.is_plugin_added::<ScrollBoxPlugin>() {
+            app.add_plugins(ScrollBoxPlugin);
+        }
+        app.insert_resource(DefaultSourceFilePath(default_source_absolute_file_path))
             .insert_resource(AssetBrowserLocation::default())
             .insert_resource(DirectoryContent::default())
+            .init_resource::<AssetBrowserFilter>()
+            .init_resource::<LastFileClick>()
+            .add_event::<AssetSelected>()
+            .add_event::<AssetDoubleClicked>()
             .add_observer(ui::on_pane_creation)
             .add_systems(Startup, io::task::fetch_directory_content)
             // .add_systems(Update, button_interaction)
@@ -62,7 +68,7 @@ impl Plugin for AssetBrowserPanePlugin {
             .add_systems(
                 Update,
                 ui::directory_content::refresh_ui
-                    .run_if(directory_content_as_changed)
+                    .run_if(directory_content_as_changed.or(filter_as_changed))
                     .after(io::task::poll_task),
             )
             .add_systems(
@@ -72,6 +78,14 @@ impl Plugin for AssetBrowserPanePlugin {
                     ui::directory_content::refresh_context_menu,
                 )
                     .run_if(location_as_changed),
+            )
+            .add_systems(
+                Update,
+                (
+                    ui::top_bar::update_filter_buttons,
+                    ui::directory_content::highlight_selected_file
+                        .after(ui::directory_content::refresh_ui),
+                ),
             );
     }
 }
@@ -96,8 +110,83 @@ pub(crate) fn directory_content_as_changed(directory_content: Res<DirectoryConte
     directory_content.is_changed()
 }
 
+/// Restrict which files are displayed by the asset browser
+/// Sources and folders are always displayed so that navigation is never blocked by the filter
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq)]
+pub struct AssetBrowserFilter {
+    /// The file extensions to display, without the leading dot (e.g. `"png"` or `"scn.ron"`)
+    /// `None` display every file
+    pub extensions: Option<Vec<String>>,
+}
+
+impl AssetBrowserFilter {
+    /// Only display files ending with one of the given extensions
+    pub fn new(extensions: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            extensions: Some(extensions.into_iter().map(Into::into).collect()),
+        }
+    }
+
+    /// Only display scene files
+    pub fn scenes() -> Self {
+        Self::new(["gltf", "glb", "scn", "scn.ron"])
+    }
+
+    /// Only display image files
+    pub fn images() -> Self {
+        Self::new(["png", "jpg", "jpeg", "ktx2", "hdr", "exr"])
+    }
+
+    /// Only display audio files
+    pub fn audio() -> Self {
+        Self::new(["ogg", "wav", "mp3", "flac"])
+    }
+
+    /// Check if a file is displayed with this filter
+    /// The comparison is case insensitive
+    pub fn matches(&self, file_name: &str) -> bool {
+        let Some(extensions) = &self.extensions else {
+            return true;
+        };
+        let file_name = file_name.to_lowercase();
+        extensions
+            .iter()
+            .any(|extension| file_name.ends_with(&format!(".{}", extension.to_lowercase())))
+    }
+}
+
+/// Check if the [`AssetBrowserFilter`] has changed, which require the directory content to be displayed again
+pub(crate) fn filter_as_changed(filter: Res<AssetBrowserFilter>) -> bool {
+    filter.is_changed()
+}
+
+/// Event sent when a file of the asset browser is clicked
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct AssetSelected {
+    /// The path of the selected asset, including its source
+    pub path: AssetPath<'static>,
+}
+
+/// Event sent when a file of the asset browser is double-clicked
+/// An [`AssetSelected`] event is always sent before this one for the first click
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct AssetDoubleClicked {
+    /// The path of the double-clicked asset, including its source
+    pub path: AssetPath<'static>,
+}
+
+/// Component present on every file node of the asset browser
+/// It can be used to know which asset is being dragged out of the asset browser
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct AssetBrowserFile(pub AssetPath<'static>);
+
+/// The last file node that was clicked, used to detect double-clicks
+#[derive(Resource, Default)]
+pub(crate) struct LastFileClick(pub Option<(Entity, Duration)>);
+
+/// The absolute path on disk of the default asset source
 #[derive(Resource)]
-struct DefaultSourceFilePath(pub PathBuf);
+pub struct DefaultSourceFilePath(pub PathBuf);
 
 /// System Set to set up the Asset Browser.
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
@@ -120,3 +209,31 @@ impl Default for AssetBrowserLocation {
         }
     }
 }
+
+impl AssetBrowserLocation {
+    /// Build the [`AssetPath`] of a file located in the current directory
+    /// Return `None` if no source is selected
+    pub fn asset_path(&self, file_name: &str) -> Option<AssetPath<'static>> {
+        let source_id = self.source_id.clone()?;
+        Some(AssetPath::from(self.path.join(file_name)).with_source(source_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_matches() {
+        let filter = AssetBrowserFilter::scenes();
+        assert!(filter.matches("level.glb"));
+        assert!(filter.matches("Level.GLTF"));
+        assert!(filter.matches("level.scn.ron"));
+        assert!(filter.matches("level.SCN.RON"));
+        assert!(filter.matches("level.scn"));
+        assert!(!filter.matches("settings.ron"));
+        assert!(!filter.matches("glb"));
+        assert!(!filter.matches("level.png"));
+        assert!(AssetBrowserFilter::default().matches("settings.ron"));
+    }
+}
@@ -1,9 +1,15 @@
-use bevy::{asset::io::AssetSourceId, prelude::*};
+use bevy::{
+    asset::{io::AssetSourceId, AssetPath},
+    prelude::*,
+};
 use bevy_context_menu::{ContextMenu, ContextMenuOption};
 use bevy_editor_styles::Theme;
 use bevy_scroll_box::{spawn_scroll_box, ScrollBox, ScrollBoxContent};
 
-use crate::{io, AssetBrowserLocation, DefaultSourceFilePath, DirectoryContent, Entry};
+use crate::{
+    io, AssetBrowserFile, AssetBrowserFilter, AssetBrowserLocation, AssetSelected,
+    DefaultSourceFilePath, DirectoryContent, Entry,
+};
 
 use crate::ui::nodes::{spawn_file_node, spawn_folder_node, spawn_source_node};
 
@@ -11,10 +17,37 @@ use crate::ui::nodes::{spawn_file_node, spawn_folder_node, spawn_source_node};
 #[derive(Component)]
 pub(crate) struct AssetBrowserContent;
 
+/// Outline the file node of the last [`AssetSelected`] asset, also once the directory content is regenerated
+pub(crate) fn highlight_selected_file(
+    mut selected_events: EventReader<AssetSelected>,
+    mut selected: Local<Option<AssetPath<'static>>>,
+    theme: Res<Theme>,
+    mut file_query: Query<(Ref<AssetBrowserFile>, &mut BorderColor)>,
+) {
+    let selection_changed = match selected_events.read().last() {
+        Some(event) => {
+            *selected = Some(event.path.clone());
+            true
+        }
+        None => false,
+    };
+    for (file, mut border_color) in &mut file_query {
+        if !selection_changed && !file.is_added() {
+            continue;
+        }
+        border_color.0 = if selected.as_ref() == Some(&file.0) {
+            theme.text.text_color
+        } else {
+            Color::NONE
+        };
+    }
+}
+
 /// Spawn the directory content UI
 pub(crate) fn spawn_directory_content<'a>(
     commands: &'a mut Commands,
     directory_content: &Res<DirectoryContent>,
+    filter: &Res<AssetBrowserFilter>,
     theme: &Res<Theme>,
     asset_server: &Res<AssetServer>,
     location: &Res<AssetBrowserLocation>,
@@ -38,6 +71,7 @@ pub(crate) fn spawn_directory_content<'a>(
                 commands,
                 content_list,
                 directory_content,
+                filter,
                 asset_server,
                 location,
                 theme,
@@ -72,6 +106,7 @@ fn asset_browser_context_menu() -> ContextMenu {
 }
 
 /// Refresh the UI with the content of the current [`AssetBrowserLocation`]
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn refresh_ui(
     mut commands: Commands,
     content_list_query: Query<(Entity, Option<&Children>), With<ScrollBoxContent>>,
@@ -79,6 +114,7 @@ pub(crate) fn refresh_ui(
     asset_server: Res<AssetServer>,
     location: Res<AssetBrowserLocation>,
     directory_content: Res<DirectoryContent>,
+    filter: Res<AssetBrowserFilter>,
     mut query_scrollbox: Query<&mut ScrollBox, With<AssetBrowserContent>>,
 ) {
     for (content_list_entity, content_list_children) in content_list_query.iter() {
@@ -87,6 +123,7 @@ pub(crate) fn refresh_ui(
             &mut commands,
             content_list_entity,
             &directory_content,
+            &filter,
             &asset_server,
             &location,
             &theme,
@@ -109,10 +146,12 @@ fn despawn_content_entries(commands: &mut Commands, container: Entity, entries:
 }
 
 /// Spawn all the content [entries](Entry) based on [`DirectoryContent`]
+/// Files not matching the [`AssetBrowserFilter`] are skipped
 fn populate_directory_content(
     commands: &mut Commands,
     parent_entity: Entity,
     directory_content: &Res<DirectoryContent>,
+    filter: &Res<AssetBrowserFilter>,
     asset_server: &Res<AssetServer>,
     location: &Res<AssetBrowserLocation>,
     theme: &Res<Theme>,
@@ -126,10 +165,11 @@ fn populate_directory_content(
                 spawn_folder_node(commands, name.clone(), asset_server, location, theme)
                     .set_parent(parent_entity);
             }
-            Entry::File(name) => {
-                spawn_file_node(commands, name.clone(), asset_server, theme)
+            Entry::File(name) if filter.matches(name) => {
+                spawn_file_node(commands, name.clone(), asset_server, location, theme)
                     .set_parent(parent_entity);
             }
+            Entry::File(_) => {}
         }
     }
 }
@@ -4,7 +4,7 @@ use bevy::prelude::*;
 use bevy_editor_styles::Theme;
 use bevy_pane_layout::PaneContentNode;
 
-use crate::{AssetBrowserLocation, DirectoryContent};
+use crate::{AssetBrowserFilter, AssetBrowserLocation, DirectoryContent};
 
 pub mod directory_content;
 mod nodes;
@@ -25,6 +25,7 @@ pub fn on_pane_creation(
     location: Res<AssetBrowserLocation>,
     asset_server: Res<AssetServer>,
     directory_content: Res<DirectoryContent>,
+    filter: Res<AssetBrowserFilter>,
 ) {
     let pane_root = trigger.entity();
     let content_node = children_query
@@ -47,6 +48,7 @@ pub fn on_pane_creation(
     directory_content::spawn_directory_content(
         &mut commands,
         &directory_content,
+        &filter,
         &theme,
         &asset_server,
         &location,
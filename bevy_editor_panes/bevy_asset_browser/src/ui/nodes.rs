@@ -8,9 +8,13 @@ use bevy::{
     winit::cursor::CursorIcon,
 };
 use bevy_context_menu::{ContextMenu, ContextMenuOption};
+use bevy_editor_core::DOUBLE_CLICK_DELAY;
 use bevy_editor_styles::Theme;
 
-use crate::{io, ui::source_id_to_string, AssetBrowserLocation};
+use crate::{
+    io, ui::source_id_to_string, AssetBrowserFile, AssetBrowserFilter, AssetBrowserLocation,
+    AssetDoubleClicked, AssetSelected, LastFileClick,
+};
 
 use super::{directory_content::delete_folder, DEFAULT_SOURCE_ID_NAME};
 
@@ -153,21 +157,63 @@ pub(crate) fn spawn_file_node<'a>(
     commands: &'a mut Commands,
     file_name: String,
     asset_server: &Res<AssetServer>,
+    location: &Res<AssetBrowserLocation>,
     theme: &Res<Theme>,
 ) -> EntityCommands<'a> {
-    let base_node = spawn_base_node(commands, theme).id();
+    let base_node = {
+        let mut ec = spawn_base_node(commands, theme);
+        ec.observe(
+            |trigger: Trigger<Pointer<Up>>,
+             time: Res<Time<Real>>,
+             mut last_click: ResMut<LastFileClick>,
+             mut selected_events: EventWriter<AssetSelected>,
+             mut double_clicked_events: EventWriter<AssetDoubleClicked>,
+             query_file: Query<&AssetBrowserFile>| {
+                if trigger.event().button != PointerButton::Primary {
+                    return;
+                }
+                let button = trigger.entity();
+                let Ok(file) = query_file.get(button) else {
+                    return;
+                };
+                let path = file.0.clone();
 
-    // Icon
+                let now = time.elapsed();
+                let is_double_click = last_click.0.is_some_and(|(entity, last_time)| {
+                    entity == button && now - last_time <= DOUBLE_CLICK_DELAY
+                });
+                if is_double_click {
+                    // Reset so that a third click start a new double-click
+                    last_click.0 = None;
+                    double_clicked_events.send(AssetDoubleClicked { path });
+                } else {
+                    last_click.0 = Some((button, now));
+                    selected_events.send(AssetSelected { path });
+                }
+            },
+        );
+        if let Some(path) = location.asset_path(&file_name) {
+            ec.insert(AssetBrowserFile(path));
+        }
+        ec.id()
+    };
+
+    // Thumbnail of the image files, icon for the others
+    let thumbnail = match location.asset_path(&file_name) {
+        Some(path) if AssetBrowserFilter::images().matches(&file_name) => asset_server.load(path),
+        _ => asset_server.load("embedded://bevy_asset_browser/assets/file_icon.png"),
+    };
     commands
         .spawn((
-            UiImage::new(asset_server.load("embedded://bevy_asset_browser/assets/file_icon.png")),
+            UiImage::new(thumbnail),
             Node {
                 height: Val::Px(50.0),
+                max_width: Val::Px(90.0),
                 ..default()
             },
         ))
         .set_parent(base_node);
-    // Folder Name
+    // File Name
     commands
         .spawn((
             Text::new(file_name),
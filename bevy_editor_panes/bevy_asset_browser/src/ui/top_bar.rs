@@ -1,7 +1,7 @@
 use bevy::{prelude::*, window::SystemCursorIcon, winit::cursor::CursorIcon};
 use bevy_editor_styles::Theme;
 
-use crate::{io, AssetBrowserLocation};
+use crate::{io, AssetBrowserFilter, AssetBrowserLocation};
 
 use super::source_id_to_string;
 
@@ -33,6 +33,7 @@ pub(crate) fn spawn_top_bar<'a>(
         ))
         .id();
     spawn_location_path_ui(commands, theme, location).set_parent(top_bar);
+    spawn_filter_selector_ui(commands, theme).set_parent(top_bar);
 
     commands.entity(top_bar)
 }
@@ -45,19 +46,24 @@ pub fn location_as_changed(location: Res<AssetBrowserLocation>) -> bool {
 pub fn refresh_ui(
     mut commands: Commands,
     root: Query<(Entity, Option<&Children>), With<TopBarNode>>,
+    filter_selector: Query<(), With<FilterSelectorNode>>,
     theme: Res<Theme>,
     location: Res<AssetBrowserLocation>,
 ) {
     for (top_bar_entity, top_bar_childrens) in root.iter() {
-        // Clear location path UI
-        if let Some(childrens) = top_bar_childrens {
-            for child in childrens.iter() {
-                commands.entity(*child).despawn_recursive();
-            }
-            commands.entity(top_bar_entity).clear_children();
+        // Clear location path UI, the filter selector stays
+        for child in top_bar_childrens
+            .into_iter()
+            .flatten()
+            .filter(|child| !filter_selector.contains(**child))
+        {
+            commands.entity(*child).despawn_recursive();
         }
         // Regenerate location path UI
-        spawn_location_path_ui(&mut commands, &theme, &location).set_parent(top_bar_entity);
+        let location_path = spawn_location_path_ui(&mut commands, &theme, &location).id();
+        commands
+            .entity(top_bar_entity)
+            .insert_children(0, &[location_path]);
     }
 }
 
@@ -228,3 +234,112 @@ pub enum LocationSegmentType {
     /// A directory segment, is a segment that represent a directory relative to the source root
     Directory,
 }
+
+/// The node containing the buttons choosing the [`AssetBrowserFilter`]
+#[derive(Component)]
+pub struct FilterSelectorNode;
+
+/// The type of assets displayed when a filter button is pressed
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AssetTypeFilter {
+    /// Display every file
+    All,
+    /// Display the files of [`AssetBrowserFilter::scenes`]
+    Scenes,
+    /// Display the files of [`AssetBrowserFilter::images`]
+    Images,
+    /// Display the files of [`AssetBrowserFilter::audio`]
+    Audio,
+}
+
+impl AssetTypeFilter {
+    const ALL: [Self; 4] = [Self::All, Self::Scenes, Self::Images, Self::Audio];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::All => "All",
+            Self::Scenes => "Scenes",
+            Self::Images => "Images",
+            Self::Audio => "Audio",
+        }
+    }
+
+    /// The filter this button sets
+    pub fn filter(self) -> AssetBrowserFilter {
+        match self {
+            Self::All => AssetBrowserFilter::default(),
+            Self::Scenes => AssetBrowserFilter::scenes(),
+            Self::Images => AssetBrowserFilter::images(),
+            Self::Audio => AssetBrowserFilter::audio(),
+        }
+    }
+}
+
+/// Spawn the buttons choosing the [`AssetBrowserFilter`], on the right of the top bar
+fn spawn_filter_selector_ui<'a>(commands: &'a mut Commands, theme: &Theme) -> EntityCommands<'a> {
+    let filter_selector = commands
+        .spawn((
+            FilterSelectorNode,
+            Node {
+                margin: UiRect::left(Val::Auto),
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+        ))
+        .id();
+
+    for asset_type in AssetTypeFilter::ALL {
+        commands
+            .spawn((
+                Button,
+                Node {
+                    padding: UiRect::axes(Val::Px(10.0), Val::Px(1.0)),
+                    margin: UiRect::horizontal(Val::Px(2.0)),
+                    ..default()
+                },
+                BackgroundColor(PATH_SEGMENT_BACKGROUND_COLOR),
+                theme.general.border_radius,
+                asset_type,
+            ))
+            .with_child((
+                Text::new(asset_type.label()),
+                TextFont {
+                    font: theme.text.font.clone(),
+                    font_size: 10.0,
+                    ..default()
+                },
+                TextColor(theme.text.text_color),
+                PickingBehavior::IGNORE,
+            ))
+            .observe(
+                move |trigger: Trigger<Pointer<Click>>, mut filter: ResMut<AssetBrowserFilter>| {
+                    if trigger.event().button != PointerButton::Primary {
+                        return;
+                    }
+                    // Don't refresh the directory content if the filter stays the same
+                    filter.set_if_neq(asset_type.filter());
+                },
+            )
+            .set_parent(filter_selector);
+    }
+    commands.entity(filter_selector)
+}
+
+/// Highlight the filter button matching the current [`AssetBrowserFilter`]
+pub(crate) fn update_filter_buttons(
+    filter: Res<AssetBrowserFilter>,
+    theme: Res<Theme>,
+    mut buttons: Query<(Ref<AssetTypeFilter>, &mut BackgroundColor)>,
+) {
+    for (asset_type, mut background) in &mut buttons {
+        if !filter.is_changed() && !asset_type.is_added() {
+            continue;
+        }
+        background.0 = if asset_type.filter() == *filter {
+            theme.button.hover_color
+        } else {
+            PATH_SEGMENT_BACKGROUND_COLOR
+        };
+    }
+}
@@ -0,0 +1,618 @@
+//! Listing the reflected fields of the components of an entity, and editing them.
+
+use std::any::TypeId;
+
+use bevy::{
+    asset::{LoadState, LoadedUntypedAsset, ReflectHandle},
+    prelude::*,
+    reflect::{DynamicEnum, DynamicVariant, Enum, ReflectRef, TypeInfo, TypeRegistry, VariantInfo},
+};
+use bevy_undo::{DynamicComponentChange, NewChange};
+
+/// How many characters of the values that can't be edited are displayed.
+const MAX_DISPLAYED_LENGTH: usize = 48;
+
+/// A field of a component of an entity that can be edited.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct Field {
+    pub(crate) entity: Entity,
+    pub(crate) component: TypeId,
+    /// The path of the field in the component, empty for the component itself.
+    pub(crate) path: String,
+    pub(crate) kind: FieldKind,
+}
+
+/// How the value of a [`Field`] is edited.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum FieldKind {
+    /// A `bool`, toggled on click.
+    Bool,
+    /// A primitive number.
+    Number,
+    /// A `String` or a [`Name`].
+    Text,
+    /// An Euler angle of a [`Quat`], in degrees.
+    Angle(usize),
+    /// A [`Color`], typed as a hexadecimal sRGB color.
+    Color,
+    /// An enum without fields, switched to its next variant on click.
+    Variant,
+    /// A [`Handle`], typed as the path of the asset to load into it.
+    Asset,
+}
+
+/// A preview of the value of a row, displayed before it.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) enum Preview {
+    /// A swatch of a [`Color`].
+    Color(Color),
+    /// A thumbnail of an image asset.
+    Image(Handle<Image>),
+}
+
+/// The assets loaded from the paths typed in asset fields, with their field.
+///
+/// They are assigned to their field once loaded, by [`assign_loaded_assets`].
+#[derive(Resource, Default)]
+pub(crate) struct PendingAssets(Vec<(Field, Handle<LoadedUntypedAsset>)>);
+
+/// Everything displayed by a row of the properties pane.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct PropertyRow {
+    pub(crate) depth: usize,
+    pub(crate) label: String,
+    /// The value as text, `None` for components and fields that contain other fields.
+    pub(crate) value: Option<String>,
+    /// The field the value can be edited in, if it can be edited.
+    pub(crate) field: Option<Field>,
+    /// The text typed so far, while the value is edited.
+    pub(crate) editing: Option<String>,
+    /// A preview of the value, displayed before it.
+    pub(crate) preview: Option<Preview>,
+}
+
+impl PropertyRow {
+    /// A row that only displays `label`.
+    pub(crate) fn header(depth: usize, label: String) -> Self {
+        Self {
+            depth,
+            label,
+            value: None,
+            field: None,
+            editing: None,
+            preview: None,
+        }
+    }
+}
+
+/// The rows of the reflected components of `entity`, sorted by name.
+pub(crate) fn entity_rows(world: &World, entity: Entity) -> Vec<PropertyRow> {
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    let entity_ref = world.entity(entity);
+
+    let mut components: Vec<_> = world
+        .inspect_entity(entity)
+        .filter_map(|info| {
+            let registration = type_registry.get(info.type_id()?)?;
+            let value = registration
+                .data::<ReflectComponent>()?
+                .reflect(entity_ref)?;
+            Some((
+                registration.type_info().type_path_table().short_path(),
+                registration.type_id(),
+                value,
+            ))
+        })
+        .collect();
+    components.sort_by_key(|(name, ..)| *name);
+
+    let mut rows = Vec::new();
+    for (name, component, value) in components {
+        ComponentRows {
+            type_registry: &type_registry,
+            entity,
+            component,
+            rows: &mut rows,
+        }
+        .push(
+            value.as_partial_reflect(),
+            String::new(),
+            name.to_string(),
+            0,
+        );
+    }
+    rows
+}
+
+/// Builds the rows of a component of an entity.
+struct ComponentRows<'a> {
+    type_registry: &'a TypeRegistry,
+    entity: Entity,
+    component: TypeId,
+    rows: &'a mut Vec<PropertyRow>,
+}
+
+impl ComponentRows<'_> {
+    /// Push the rows displaying `value`, and the fields it contains, found at `path` in the
+    /// component.
+    fn push(&mut self, value: &dyn PartialReflect, path: String, label: String, depth: usize) {
+        let (entity, component) = (self.entity, self.component);
+        let field = |path: String, kind: FieldKind| Field {
+            entity,
+            component,
+            path,
+            kind,
+        };
+        if let Some((kind, text)) = editable_value(value) {
+            self.rows.push(PropertyRow {
+                value: Some(text),
+                field: Some(field(path, kind)),
+                ..PropertyRow::header(depth, label)
+            });
+            return;
+        }
+
+        if let Some(color) = value.try_downcast_ref::<Color>() {
+            self.rows.push(PropertyRow {
+                value: Some(color.to_srgba().to_hex()),
+                field: Some(field(path, FieldKind::Color)),
+                preview: Some(Preview::Color(*color)),
+                ..PropertyRow::header(depth, label)
+            });
+            return;
+        }
+
+        // Handles are enums too, check for them first
+        if let Some(handle) = self.handle(value) {
+            self.rows.push(PropertyRow {
+                value: Some(handle.path().map(ToString::to_string).unwrap_or_default()),
+                field: Some(field(path, FieldKind::Asset)),
+                // Scenes have no thumbnail until they can be rendered to an image
+                preview: handle.try_typed::<Image>().ok().map(Preview::Image),
+                ..PropertyRow::header(depth, label)
+            });
+            return;
+        }
+
+        if let Some(rotation) = value.try_downcast_ref::<Quat>() {
+            self.rows.push(PropertyRow::header(depth, label));
+            let (x, y, z) = rotation.to_euler(EulerRot::XYZ);
+            for (axis, (label, angle)) in [("x", x), ("y", y), ("z", z)].into_iter().enumerate() {
+                self.rows.push(PropertyRow {
+                    value: Some(format!("{:.1}", angle.to_degrees())),
+                    field: Some(field(path.clone(), FieldKind::Angle(axis))),
+                    ..PropertyRow::header(depth + 1, label.to_string())
+                });
+            }
+            return;
+        }
+
+        match value.reflect_ref() {
+            ReflectRef::Struct(value) => {
+                self.rows.push(PropertyRow::header(depth, label));
+                for index in 0..value.field_len() {
+                    let (Some(name), Some(field_value)) =
+                        (value.name_at(index), value.field_at(index))
+                    else {
+                        continue;
+                    };
+                    self.push(
+                        field_value,
+                        format!("{path}.{name}"),
+                        name.to_string(),
+                        depth + 1,
+                    );
+                }
+            }
+            ReflectRef::TupleStruct(value) => {
+                self.rows.push(PropertyRow::header(depth, label));
+                for (index, field_value) in value.iter_fields().enumerate() {
+                    self.push(
+                        field_value,
+                        format!("{path}.{index}"),
+                        index.to_string(),
+                        depth + 1,
+                    );
+                }
+            }
+            ReflectRef::Enum(value) => {
+                // Only the enums without fields can be switched to another variant
+                let switchable = value.field_len() == 0 && unit_variants(value).len() > 1;
+                self.rows.push(PropertyRow {
+                    value: Some(value.variant_name().to_string()),
+                    field: switchable.then(|| field(path.clone(), FieldKind::Variant)),
+                    ..PropertyRow::header(depth, label)
+                });
+                for (index, field) in value.iter_fields().enumerate() {
+                    let name = field
+                        .name()
+                        .map_or_else(|| index.to_string(), ToString::to_string);
+                    self.push(field.value(), format!("{path}.{name}"), name, depth + 1);
+                }
+            }
+            _ => {
+                let mut text = format!("{value:?}");
+                if let Some((end, _)) = text.char_indices().nth(MAX_DISPLAYED_LENGTH) {
+                    text.truncate(end);
+                    text.push_str("...");
+                }
+                self.rows.push(PropertyRow {
+                    value: Some(text),
+                    ..PropertyRow::header(depth, label)
+                });
+            }
+        }
+    }
+
+    /// The handle in `value`, if it is an asset [`Handle`].
+    fn handle(&self, value: &dyn PartialReflect) -> Option<UntypedHandle> {
+        self.type_registry
+            .get_type_data::<ReflectHandle>(value.get_represented_type_info()?.type_id())?
+            .downcast_handle_untyped(value.try_as_reflect()?.as_any())
+    }
+}
+
+/// The names of the variants without fields of the enum `value`.
+fn unit_variants(value: &dyn Enum) -> Vec<&'static str> {
+    let Some(TypeInfo::Enum(info)) = value.get_represented_type_info() else {
+        return Vec::new();
+    };
+    info.iter()
+        .filter(|variant| matches!(variant, VariantInfo::Unit(_)))
+        .map(VariantInfo::name)
+        .collect()
+}
+
+/// How `value` is edited and its text, if it can be edited.
+fn editable_value(value: &dyn PartialReflect) -> Option<(FieldKind, String)> {
+    if let Some(value) = value.try_downcast_ref::<bool>() {
+        Some((FieldKind::Bool, value.to_string()))
+    } else if let Some(value) = value.try_downcast_ref::<String>() {
+        Some((FieldKind::Text, value.clone()))
+    } else if let Some(name) = value.try_downcast_ref::<Name>() {
+        Some((FieldKind::Text, name.as_str().to_string()))
+    } else if is_number(value) {
+        Some((FieldKind::Number, format!("{value:?}")))
+    } else {
+        None
+    }
+}
+
+fn is_number(value: &dyn PartialReflect) -> bool {
+    macro_rules! represents_any {
+        ($($ty:ty),*) => {
+            $(value.represents::<$ty>())||*
+        };
+    }
+    represents_any!(f32, f64, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize)
+}
+
+/// Set `value` to the number in `text`, returns whether it is a valid number of its type.
+fn set_number(value: &mut dyn PartialReflect, text: &str) -> bool {
+    macro_rules! parse_into {
+        ($($ty:ty),*) => {
+            $(if let Some(value) = value.try_downcast_mut::<$ty>() {
+                return text.parse().map(|parsed: $ty| *value = parsed).is_ok();
+            })*
+        };
+    }
+    if let Some(value) = value.try_downcast_mut::<f32>() {
+        return text
+            .parse::<f32>()
+            .ok()
+            .filter(|parsed| parsed.is_finite())
+            .map(|parsed| *value = parsed)
+            .is_some();
+    }
+    if let Some(value) = value.try_downcast_mut::<f64>() {
+        return text
+            .parse::<f64>()
+            .ok()
+            .filter(|parsed| parsed.is_finite())
+            .map(|parsed| *value = parsed)
+            .is_some();
+    }
+    parse_into!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+    false
+}
+
+/// Switch the enum `value` to its next variant without fields.
+fn switch_variant(value: &mut dyn PartialReflect) -> bool {
+    let ReflectRef::Enum(current) = value.reflect_ref() else {
+        return false;
+    };
+    let variants = unit_variants(current);
+    let Some(index) = variants
+        .iter()
+        .position(|variant| *variant == current.variant_name())
+    else {
+        return false;
+    };
+    let next = DynamicEnum::new(variants[(index + 1) % variants.len()], DynamicVariant::Unit);
+    value.try_apply(&next).is_ok()
+}
+
+/// Set `value`, edited as `kind`, to `text`. Returns whether `text` is a valid value.
+fn set_value(value: &mut dyn PartialReflect, kind: FieldKind, text: &str) -> bool {
+    match kind {
+        FieldKind::Bool => value
+            .try_downcast_mut::<bool>()
+            .map(|value| *value = !*value)
+            .is_some(),
+        FieldKind::Number => set_number(value, text.trim()),
+        FieldKind::Text => {
+            if let Some(value) = value.try_downcast_mut::<String>() {
+                *value = text.to_string();
+                true
+            } else if let Some(name) = value.try_downcast_mut::<Name>() {
+                name.set(text.to_string());
+                true
+            } else {
+                false
+            }
+        }
+        FieldKind::Angle(axis) => {
+            let (Some(rotation), Ok(degrees)) =
+                (value.try_downcast_mut::<Quat>(), text.trim().parse::<f32>())
+            else {
+                return false;
+            };
+            if !degrees.is_finite() {
+                return false;
+            }
+            let (x, y, z) = rotation.to_euler(EulerRot::XYZ);
+            let mut angles = [x, y, z];
+            angles[axis] = degrees.to_radians();
+            *rotation = Quat::from_euler(EulerRot::XYZ, angles[0], angles[1], angles[2]);
+            true
+        }
+        FieldKind::Color => {
+            let (Some(color), Ok(srgba)) =
+                (value.try_downcast_mut::<Color>(), Srgba::hex(text.trim()))
+            else {
+                return false;
+            };
+            *color = srgba.into();
+            true
+        }
+        FieldKind::Variant => switch_variant(value),
+        // The asset is only assigned once loaded, by `assign_asset`
+        FieldKind::Asset => false,
+    }
+}
+
+/// Set `field` to the value in `text`, or toggle it if it's a boolean or an enum, and record the
+/// change so it can be undone. Invalid values are ignored.
+///
+/// The asset at the path in `text` is loaded for asset fields, it's assigned once loaded.
+pub(crate) fn apply_edit(world: &mut World, field: &Field, text: &str) {
+    if field.kind == FieldKind::Asset {
+        let path = text.trim();
+        if !path.is_empty() {
+            let asset = world
+                .resource::<AssetServer>()
+                .load_untyped(path.to_string());
+            world
+                .resource_mut::<PendingAssets>()
+                .0
+                .push((field.clone(), asset));
+        }
+        return;
+    }
+    edit_field(world, field, |value, _| set_value(value, field.kind, text));
+}
+
+/// Assign the assets loaded for asset fields to their field.
+pub(crate) fn assign_loaded_assets(
+    mut commands: Commands,
+    mut pending: ResMut<PendingAssets>,
+    asset_server: Res<AssetServer>,
+    loaded_assets: Res<Assets<LoadedUntypedAsset>>,
+) {
+    if pending.0.is_empty() {
+        return;
+    }
+    pending.0.retain(|(field, asset)| {
+        if let Some(loaded) = loaded_assets.get(asset) {
+            let field = field.clone();
+            let asset = loaded.handle.clone();
+            commands.queue(move |world: &mut World| assign_asset(world, &field, asset));
+            return false;
+        }
+        if let LoadState::Failed(error) = asset_server.load_state(asset) {
+            warn!("Failed to load the asset to assign: {error}");
+            return false;
+        }
+        true
+    });
+}
+
+/// Set the handle of `field` to `asset`, and record the change so it can be undone. Assets of
+/// another type than the one of the handle are ignored.
+fn assign_asset(world: &mut World, field: &Field, asset: UntypedHandle) {
+    edit_field(world, field, |value, type_registry| {
+        let Some(reflect_handle) = value
+            .get_represented_type_info()
+            .and_then(|info| type_registry.get_type_data::<ReflectHandle>(info.type_id()))
+        else {
+            return false;
+        };
+        if reflect_handle.asset_type_id() != asset.type_id() {
+            warn!(
+                "{} can't be assigned to {}, it's another type of asset",
+                asset.path().map(ToString::to_string).unwrap_or_default(),
+                field.path
+            );
+            return false;
+        }
+        value
+            .try_apply(reflect_handle.typed(asset).as_partial_reflect())
+            .is_ok()
+    });
+}
+
+/// Change the value of `field` with `edit`, which returns whether it changed the value, and
+/// record the change so it can be undone.
+fn edit_field(
+    world: &mut World,
+    field: &Field,
+    edit: impl FnOnce(&mut dyn PartialReflect, &TypeRegistry) -> bool,
+) {
+    if !world.entities().contains(field.entity) {
+        return;
+    }
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let Some(reflect_component) = type_registry
+        .get_type_data::<ReflectComponent>(field.component)
+        .cloned()
+    else {
+        return;
+    };
+
+    let mut entity_mut = world.entity_mut(field.entity);
+    let Some(mut component) = reflect_component.reflect_mut(&mut entity_mut) else {
+        return;
+    };
+    let old_value = component.clone_value();
+    let component_value = component.bypass_change_detection().as_partial_reflect_mut();
+    let value = if field.path.is_empty() {
+        Some(component_value)
+    } else {
+        component_value.reflect_path_mut(field.path.as_str()).ok()
+    };
+    if !value.is_some_and(|value| edit(value, &type_registry)) {
+        return;
+    }
+    component.set_changed();
+    let new_value = component.clone_value();
+
+    world.send_event(NewChange::new(DynamicComponentChange::new(
+        field.entity,
+        reflect_component,
+        old_value,
+        new_value,
+    )));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edited_world() -> World {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world.init_resource::<Events<NewChange>>();
+        {
+            let mut type_registry = world.resource::<AppTypeRegistry>().write();
+            type_registry.register::<Transform>();
+            type_registry.register::<Name>();
+            type_registry.register::<Visibility>();
+            type_registry.register::<BackgroundColor>();
+        }
+        world
+    }
+
+    fn find_field(world: &World, entity: Entity, label: &str) -> Field {
+        entity_rows(world, entity)
+            .into_iter()
+            .find(|row| row.label == label)
+            .and_then(|row| row.field)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_list_transform_fields() {
+        let mut world = edited_world();
+        let entity = world.spawn(Transform::from_xyz(1.0, 2.0, 3.0)).id();
+
+        let rows = entity_rows(&world, entity);
+        let labels: Vec<&str> = rows.iter().map(|row| row.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            [
+                "Transform",
+                "translation",
+                "x",
+                "y",
+                "z",
+                "rotation",
+                "x",
+                "y",
+                "z",
+                "scale",
+                "x",
+                "y",
+                "z"
+            ]
+        );
+        assert_eq!(rows[2].value.as_deref(), Some("1.0"));
+        assert_eq!(rows[2].field.as_ref().unwrap().path, ".translation.x");
+        assert_eq!(rows[6].field.as_ref().unwrap().kind, FieldKind::Angle(0));
+    }
+
+    #[test]
+    fn test_edit_number_and_angle() {
+        let mut world = edited_world();
+        let entity = world.spawn(Transform::default()).id();
+        let rows = entity_rows(&world, entity);
+
+        apply_edit(&mut world, rows[3].field.as_ref().unwrap(), "4.5");
+        apply_edit(&mut world, rows[8].field.as_ref().unwrap(), "90");
+        // Ignored
+        apply_edit(&mut world, rows[10].field.as_ref().unwrap(), "wide");
+
+        let transform = world.get::<Transform>(entity).unwrap();
+        assert_eq!(transform.translation, Vec3::new(0.0, 4.5, 0.0));
+        assert!(transform
+            .rotation
+            .abs_diff_eq(Quat::from_rotation_z(90f32.to_radians()), 1e-5));
+        assert_eq!(transform.scale, Vec3::ONE);
+        assert_eq!(world.resource::<Events<NewChange>>().len(), 2);
+    }
+
+    #[test]
+    fn test_edit_name() {
+        let mut world = edited_world();
+        let entity = world.spawn(Name::new("Player")).id();
+
+        let field = find_field(&world, entity, "Name");
+        assert_eq!(field.kind, FieldKind::Text);
+        apply_edit(&mut world, &field, "Enemy");
+
+        assert_eq!(world.get::<Name>(entity).unwrap().as_str(), "Enemy");
+    }
+
+    #[test]
+    fn test_switch_variant() {
+        let mut world = edited_world();
+        let entity = world.spawn(Visibility::Inherited).id();
+
+        let field = find_field(&world, entity, "Visibility");
+        assert_eq!(field.kind, FieldKind::Variant);
+        apply_edit(&mut world, &field, "");
+
+        assert_eq!(world.get::<Visibility>(entity), Some(&Visibility::Hidden));
+    }
+
+    #[test]
+    fn test_edit_color() {
+        let mut world = edited_world();
+        let entity = world.spawn(BackgroundColor(Color::WHITE)).id();
+
+        let row = entity_rows(&world, entity)
+            .into_iter()
+            .find(|row| row.label == "0")
+            .unwrap();
+        assert_eq!(row.value.as_deref(), Some("#FFFFFF"));
+        assert_eq!(row.preview, Some(Preview::Color(Color::WHITE)));
+        apply_edit(&mut world, row.field.as_ref().unwrap(), "#FF0000");
+        // Ignored
+        apply_edit(&mut world, row.field.as_ref().unwrap(), "red");
+
+        assert_eq!(
+            world.get::<BackgroundColor>(entity).unwrap().0,
+            Color::srgb(1.0, 0.0, 0.0)
+        );
+    }
+}
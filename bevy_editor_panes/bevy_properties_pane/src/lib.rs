@@ -1,19 +1,223 @@
 //! An interactive, reflection-based inspector for Bevy ECS data in running applications.
 //!
 //! Data can be viewed and modified in real-time, with changes being reflected in the application.
+//!
+//! The properties pane lists the reflected components of the primary entity of the
+//! [`EditorSelection`] and their fields. Clicking a boolean toggles it, and clicking an enum
+//! without fields switches it to its next variant. Clicking a number, a string, a [`Name`], an
+//! angle of a rotation, a [`Color`] or an asset [`Handle`] starts editing it: Enter applies the
+//! typed value, Escape cancels. Colors are typed in hexadecimal and shown with a swatch, handles
+//! are typed as the path of the asset to load and images are shown with a thumbnail. Every edit
+//! can be undone. The fields of the other types are only displayed.
+
+use bevy::{
+    ecs::{archetype::ArchetypeId, system::SystemChangeTick},
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
+use bevy_editor_core::{EditorCorePlugin, EditorSelection, TextInputFocus};
+use bevy_editor_styles::Theme;
+use bevy_pane_layout::PaneRegistry;
+use bevy_scroll_box::ScrollBoxPlugin;
+use bevy_undo::UndoPlugin;
+use fields::{Field, PendingAssets, PropertyRow};
+use ui::{PropertiesList, PropertiesNode, RowParts};
+
+mod fields;
+mod ui;
+
+/// The Bevy Properties Pane Plugin.
+pub struct PropertiesPanePlugin;
+
+impl Plugin for PropertiesPanePlugin {
+    fn build(&self, app: &mut App) {
+        app.world_mut()
+            .get_resource_or_init::<PaneRegistry>()
+            .register("Properties", |mut commands, pane_root| {
+                commands.entity(pane_root).insert(PropertiesNode);
+            });
+
+        if !app.is_plugin_added::<EditorCorePlugin>() {
+            app.add_plugins(EditorCorePlugin);
+        }
+        if !app.is_plugin_added::<ScrollBoxPlugin>() {
+            app.add_plugins(ScrollBoxPlugin);
+        }
+        if !app.is_plugin_added::<UndoPlugin>() {
+            app.add_plugins(UndoPlugin);
+        }
+        app.init_resource::<PropertiesState>()
+            .init_resource::<PendingAssets>()
+            .add_observer(ui::on_pane_creation)
+            .add_systems(
+                Update,
+                (
+                    handle_edit_input,
+                    fields::assign_loaded_assets,
+                    refresh_properties,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Interaction state of the properties panes.
+#[derive(Resource, Default)]
+struct PropertiesState {
+    /// The field being edited, if any.
+    editing: Option<Edit>,
+}
+
+struct Edit {
+    field: Field,
+    text: String,
+    /// The node the value is edited from, which owns the [`TextInputFocus`].
+    owner: Entity,
+}
 
-/// an add function that adds two numbers
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
+/// Update the rows of the properties panes when the selection, the components or the edited text changed.
+///
+/// The rows are only listed again when the displayed entity gained, lost or changed a component
+/// since the last run. The row nodes are reused by position, so the nodes being clicked stay the
+/// same.
+#[allow(clippy::too_many_arguments)]
+fn refresh_properties(
+    world: &World,
+    ticks: SystemChangeTick,
+    mut commands: Commands,
+    theme: Res<Theme>,
+    selection: Res<EditorSelection>,
+    state: Res<PropertiesState>,
+    mut displayed_entity: Local<Option<(Entity, ArchetypeId)>>,
+    mut displayed_rows: Local<Vec<PropertyRow>>,
+    list_query: Query<(Entity, Option<&Children>), With<PropertiesList>>,
+    new_list_query: Query<(), Added<PropertiesList>>,
+    row_query: Query<&RowParts>,
+) {
+    let entity = selection
+        .primary()
+        .and_then(|entity| world.get_entity(entity).ok());
+    let entity_changed = match entity {
+        Some(entity) => {
+            *displayed_entity != Some((entity.id(), entity.archetype().id()))
+                || entity.archetype().components().any(|id| {
+                    entity
+                        .get_change_ticks_by_id(id)
+                        .is_some_and(|change_ticks| {
+                            change_ticks.is_changed(ticks.last_run(), ticks.this_run())
+                        })
+                })
+        }
+        None => displayed_entity.is_some(),
+    };
+    if !entity_changed
+        && !selection.is_changed()
+        && !state.is_changed()
+        && new_list_query.is_empty()
+    {
+        return;
+    }
+    *displayed_entity = entity.map(|entity| (entity.id(), entity.archetype().id()));
+
+    let mut rows = match entity {
+        Some(entity) => fields::entity_rows(world, entity.id()),
+        None => vec![PropertyRow::header(0, "No entity selected".to_string())],
+    };
+    if let Some(edit) = &state.editing {
+        if let Some(row) = rows
+            .iter_mut()
+            .find(|row| row.field.as_ref() == Some(&edit.field))
+        {
+            row.editing = Some(edit.text.clone());
+        }
+    }
+
+    if rows == *displayed_rows && new_list_query.is_empty() {
+        return;
+    }
+
+    for (list, list_children) in &list_query {
+        let row_nodes: Vec<(Entity, &RowParts)> = list_children
+            .into_iter()
+            .flatten()
+            .filter_map(|node| Some((*node, row_query.get(*node).ok()?)))
+            .collect();
+
+        for (index, row) in rows.iter().enumerate() {
+            match row_nodes.get(index) {
+                Some(&(node, parts)) => {
+                    if displayed_rows.get(index) != Some(row) {
+                        ui::update_row(&mut commands, &theme, node, parts, row);
+                    }
+                }
+                None => {
+                    ui::spawn_row(&mut commands, &theme, row).set_parent(list);
+                }
+            }
+        }
+        for &(node, _) in row_nodes.iter().skip(rows.len()) {
+            commands.entity(node).despawn_recursive();
+        }
+    }
+    *displayed_rows = rows;
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Edit the value of the field being edited from the keyboard.
+///
+/// Enter applies the new value, Escape cancels. Selecting another entity or focusing another text
+/// input cancels too.
+fn handle_edit_input(
+    mut commands: Commands,
+    mut state: ResMut<PropertiesState>,
+    mut text_input_focus: ResMut<TextInputFocus>,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    selection: Res<EditorSelection>,
+) {
+    if let Some(edit) = &state.editing {
+        if !text_input_focus.is_focused_by(edit.owner)
+            || selection.primary() != Some(edit.field.entity)
+        {
+            text_input_focus.release(edit.owner);
+            state.editing = None;
+        }
+    }
+    if state.editing.is_none() {
+        keyboard_events.clear();
+        return;
+    }
+
+    let mut apply = None;
+    let edit = state.editing.as_mut().unwrap();
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Enter => apply = Some(true),
+            Key::Escape => apply = Some(false),
+            Key::Backspace => {
+                edit.text.pop();
+            }
+            Key::Space => edit.text.push(' '),
+            Key::Character(characters) => edit.text.push_str(characters),
+            _ => {}
+        }
+        if apply.is_some() {
+            break;
+        }
+    }
 
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+    let Some(apply) = apply else {
+        return;
+    };
+    let edit = state.editing.take().unwrap();
+    text_input_focus.release(edit.owner);
+    if apply {
+        commands.queue(move |world: &mut World| {
+            fields::apply_edit(world, &edit.field, &edit.text);
+        });
     }
 }
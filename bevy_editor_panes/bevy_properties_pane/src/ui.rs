@@ -0,0 +1,237 @@
+use bevy::{ecs::system::EntityCommands, prelude::*};
+use bevy_editor_core::TextInputFocus;
+use bevy_editor_styles::Theme;
+use bevy_pane_layout::PaneContentNode;
+use bevy_scroll_box::spawn_scroll_box;
+
+use crate::{
+    fields::{self, Field, FieldKind, Preview, PropertyRow},
+    Edit, PropertiesState,
+};
+
+/// Indentation of the fields under the component they belong to.
+const INDENT_WIDTH: f32 = 14.0;
+
+/// Width of the labels of the fields, so that the values are aligned.
+const LABEL_WIDTH: f32 = 90.0;
+
+/// Size of the color swatches and image thumbnails displayed before the values.
+const PREVIEW_SIZE: f32 = 16.0;
+
+/// Root UI node of the properties pane.
+#[derive(Component)]
+pub(crate) struct PropertiesNode;
+
+/// The node containing the rows of a properties pane.
+#[derive(Component)]
+pub(crate) struct PropertiesList;
+
+/// The nodes of a row of the properties pane.
+#[derive(Component)]
+pub(crate) struct RowParts {
+    label: Entity,
+    preview: Entity,
+    value: Entity,
+}
+
+/// The field a value node edits, and its current value.
+#[derive(Component)]
+struct FieldValue {
+    field: Field,
+    text: String,
+}
+
+/// Spawn the properties list once the pane is created
+pub(crate) fn on_pane_creation(
+    trigger: Trigger<OnAdd, PropertiesNode>,
+    mut commands: Commands,
+    theme: Res<Theme>,
+    children_query: Query<&Children>,
+    content: Query<&PaneContentNode>,
+) {
+    let pane_root = trigger.entity();
+    let content_node = children_query
+        .iter_descendants(pane_root)
+        .find(|e| content.contains(*e))
+        .unwrap();
+
+    commands.entity(content_node).insert(Node {
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        display: Display::Flex,
+        flex_direction: FlexDirection::Column,
+        ..default()
+    });
+
+    spawn_scroll_box(
+        &mut commands,
+        &theme,
+        Overflow::scroll_y(),
+        Some(|commands: &mut Commands, content_list: Entity| {
+            commands
+                .spawn((
+                    PropertiesList,
+                    Node {
+                        width: Val::Percent(100.0),
+                        flex_direction: FlexDirection::Column,
+                        ..default()
+                    },
+                ))
+                .set_parent(content_list);
+        }),
+    )
+    .set_parent(content_node);
+}
+
+pub(crate) fn spawn_row<'a>(
+    commands: &'a mut Commands,
+    theme: &Theme,
+    row: &PropertyRow,
+) -> EntityCommands<'a> {
+    let row_node = commands.spawn_empty().id();
+    let label = spawn_text(commands, theme)
+        .insert(Node {
+            min_width: Val::Px(LABEL_WIDTH),
+            ..default()
+        })
+        .set_parent(row_node)
+        .id();
+    let preview = commands.spawn(Node::default()).set_parent(row_node).id();
+    let value = spawn_text(commands, theme)
+        .insert(Node {
+            flex_grow: 1.0,
+            ..default()
+        })
+        .set_parent(row_node)
+        .observe(on_value_click)
+        .id();
+
+    let parts = RowParts {
+        label,
+        preview,
+        value,
+    };
+    update_row(commands, theme, row_node, &parts, row);
+    commands.entity(row_node).insert(parts);
+    commands.entity(row_node)
+}
+
+/// Update the row node `row_node`, made of `parts`, to display `row`.
+pub(crate) fn update_row(
+    commands: &mut Commands,
+    theme: &Theme,
+    row_node: Entity,
+    parts: &RowParts,
+    row: &PropertyRow,
+) {
+    commands.entity(row_node).insert(Node {
+        width: Val::Percent(100.0),
+        height: Val::Px(20.0),
+        padding: UiRect::new(
+            Val::Px(4.0 + INDENT_WIDTH * row.depth as f32),
+            Val::Px(4.0),
+            Val::ZERO,
+            Val::ZERO,
+        ),
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(4.0),
+        ..default()
+    });
+
+    commands.entity(parts.label).insert((
+        Text::new(row.label.clone()),
+        TextColor(if row.value.is_some() {
+            theme.text.low_priority
+        } else {
+            theme.text.text_color
+        }),
+    ));
+
+    let preview_node = |display| Node {
+        display,
+        width: Val::Px(PREVIEW_SIZE),
+        height: Val::Px(PREVIEW_SIZE),
+        ..default()
+    };
+    let mut preview = commands.entity(parts.preview);
+    match &row.preview {
+        Some(Preview::Color(color)) => {
+            preview
+                .insert((preview_node(Display::Flex), BackgroundColor(*color)))
+                .remove::<UiImage>();
+        }
+        Some(Preview::Image(image)) => {
+            preview.insert((
+                preview_node(Display::Flex),
+                BackgroundColor(Color::NONE),
+                UiImage::new(image.clone()),
+            ));
+        }
+        None => {
+            preview
+                .insert(preview_node(Display::None))
+                .remove::<UiImage>();
+        }
+    }
+
+    let (text, color) = match (&row.editing, &row.value) {
+        (Some(editing), _) => (format!("{editing}|"), theme.text.high_priority),
+        (None, Some(value)) if row.field.is_some() => (value.clone(), theme.text.text_color),
+        (None, Some(value)) => (value.clone(), theme.text.low_priority),
+        (None, None) => (String::new(), theme.text.text_color),
+    };
+    let mut value = commands.entity(parts.value);
+    value.insert((Text::new(text), TextColor(color)));
+    match (&row.field, &row.value) {
+        (Some(field), Some(text)) => {
+            value.insert(FieldValue {
+                field: field.clone(),
+                text: text.clone(),
+            });
+        }
+        _ => {
+            value.remove::<FieldValue>();
+        }
+    }
+}
+
+fn spawn_text<'a>(commands: &'a mut Commands, theme: &Theme) -> EntityCommands<'a> {
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font: theme.text.font.clone(),
+            font_size: 12.0,
+            ..default()
+        },
+    ))
+}
+
+/// Toggle the clicked boolean, switch the clicked enum to its next variant, or start editing the
+/// clicked value.
+fn on_value_click(
+    mut trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    mut state: ResMut<PropertiesState>,
+    mut text_input_focus: ResMut<TextInputFocus>,
+    value_query: Query<&FieldValue>,
+) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    let Ok(value) = value_query.get(trigger.entity()) else {
+        return;
+    };
+    trigger.propagate(false);
+
+    if matches!(value.field.kind, FieldKind::Bool | FieldKind::Variant) {
+        let field = value.field.clone();
+        commands.queue(move |world: &mut World| fields::apply_edit(world, &field, ""));
+        return;
+    }
+    state.editing = Some(Edit {
+        field: value.field.clone(),
+        text: value.text.clone(),
+        owner: trigger.entity(),
+    });
+    text_input_focus.0 = Some(trigger.entity());
+}
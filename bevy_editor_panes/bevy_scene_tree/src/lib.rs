@@ -1,17 +1,263 @@
 //! An interactive, collapsible tree view for hierarchical ECS data in Bevy.
+//!
+//! The scene tree lists every entity of the edited world with a [`Transform`], nested under their
+//! parents. Rows can be clicked to change the [`EditorSelection`] (Ctrl toggles an entity, Shift
+//! selects a range), double-clicked to rename the entity's [`Name`], and dragged onto each other
+//! to reparent entities. Each row also has toggles for the entity's [`Visibility`] and [`Locked`]
+//! state. Renaming, reparenting and toggling can be undone.
 
-/// an add function that adds two numbers
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
+use std::time::Duration;
+
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_editor_core::{
+    EditedEntityFilter, EditorCorePlugin, EditorSelection, Locked, TextInputFocus,
+};
+use bevy_editor_styles::Theme;
+use bevy_pane_layout::PaneRegistry;
+use bevy_scroll_box::ScrollBoxPlugin;
+use bevy_undo::{AddedComponent, ComponentChange, NewChange, UndoPlugin};
+use ui::{RowParts, SceneTreeList, SceneTreeNode, SceneTreeRow};
+
+mod ui;
+
+/// The Bevy Scene Tree Plugin.
+pub struct SceneTreePlugin;
+
+impl Plugin for SceneTreePlugin {
+    fn build(&self, app: &mut App) {
+        app.world_mut()
+            .get_resource_or_init::<PaneRegistry>()
+            .register("Scene Tree", |mut commands, pane_root| {
+                commands.entity(pane_root).insert(SceneTreeNode);
+            });
+
+        if !app.is_plugin_added::<EditorCorePlugin>() {
+            app.add_plugins(EditorCorePlugin);
+        }
+        if !app.is_plugin_added::<ScrollBoxPlugin>() {
+            app.add_plugins(ScrollBoxPlugin);
+        }
+        if !app.is_plugin_added::<UndoPlugin>() {
+            app.add_plugins(UndoPlugin);
+        }
+        app.init_resource::<SceneTreeState>()
+            .add_observer(ui::on_pane_creation)
+            .add_systems(Update, (handle_rename_input, refresh_rows).chain());
+    }
+}
+
+/// Interaction state of the scene tree, shared by all the scene tree panes.
+#[derive(Resource, Default)]
+struct SceneTreeState {
+    /// Entities whose children are hidden.
+    collapsed: HashSet<Entity>,
+    /// The entities of the displayed rows, in display order.
+    rows: Vec<Entity>,
+    /// The entity Shift-click ranges start from.
+    anchor: Option<Entity>,
+    /// The last row clicked, used to detect double-clicks.
+    last_click: Option<(Entity, Duration)>,
+    /// The entity being renamed, if any.
+    renaming: Option<Rename>,
+}
+
+struct Rename {
+    entity: Entity,
+    text: String,
+}
+
+/// Everything displayed by a row of the scene tree.
+#[derive(Clone, PartialEq)]
+struct TreeRow {
+    entity: Entity,
+    depth: usize,
+    label: String,
+    /// `Some(expanded)` if the entity has children.
+    expanded: Option<bool>,
+    selected: bool,
+    renaming: bool,
+    hidden: bool,
+    locked: bool,
+}
+
+/// Update the rows of the scene tree panes when the hierarchy, the selection or any displayed state changed.
+#[allow(clippy::too_many_arguments)]
+fn refresh_rows(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    selection: Res<EditorSelection>,
+    mut state: ResMut<SceneTreeState>,
+    mut displayed_rows: Local<Vec<TreeRow>>,
+    scene_query: Query<
+        (
+            Entity,
+            Option<&Name>,
+            Option<&Children>,
+            Option<&Parent>,
+            Option<&Visibility>,
+            Has<Locked>,
+        ),
+        EditedEntityFilter,
+    >,
+    list_query: Query<(Entity, Option<&Children>), With<SceneTreeList>>,
+    new_list_query: Query<(), Added<SceneTreeList>>,
+    row_query: Query<(&SceneTreeRow, &RowParts)>,
+) {
+    // Entities whose parent isn't listed are shown at the root of the tree
+    let mut roots: Vec<Entity> = scene_query
+        .iter()
+        .filter(|(_, _, _, parent, ..)| !parent.is_some_and(|p| scene_query.contains(p.get())))
+        .map(|(entity, ..)| entity)
+        .collect();
+    roots.sort();
+
+    let mut rows = Vec::new();
+    let mut stack: Vec<(Entity, usize)> = roots.into_iter().rev().map(|e| (e, 0)).collect();
+    while let Some((entity, depth)) = stack.pop() {
+        let Ok((_, name, children, _, visibility, locked)) = scene_query.get(entity) else {
+            continue;
+        };
+        let children: Vec<Entity> = children
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|child| scene_query.contains(*child))
+            .collect();
+        let expanded = (!children.is_empty()).then(|| !state.collapsed.contains(&entity));
+        let (label, renaming) = match &state.renaming {
+            Some(rename) if rename.entity == entity => (format!("{}|", rename.text), true),
+            _ => (
+                name.map_or_else(|| format!("Entity {entity}"), ToString::to_string),
+                false,
+            ),
+        };
+
+        rows.push(TreeRow {
+            entity,
+            depth,
+            label,
+            expanded,
+            selected: selection.contains(entity),
+            renaming,
+            hidden: visibility == Some(&Visibility::Hidden),
+            locked,
+        });
+        if expanded == Some(true) {
+            stack.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+        }
+    }
+
+    if rows == *displayed_rows && new_list_query.is_empty() {
+        return;
+    }
+
+    state.rows = rows.iter().map(|row| row.entity).collect();
+    // Only update the rows that changed, so the rows being clicked or dragged stay the same nodes
+    let previous_rows: HashMap<Entity, &TreeRow> =
+        displayed_rows.iter().map(|row| (row.entity, row)).collect();
+    for (list, list_children) in &list_query {
+        let mut row_nodes: HashMap<Entity, (Entity, &RowParts)> = list_children
+            .into_iter()
+            .flatten()
+            .filter_map(|node| {
+                let (row, parts) = row_query.get(*node).ok()?;
+                Some((row.0, (*node, parts)))
+            })
+            .collect();
+
+        let mut ordered_nodes = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let node = match row_nodes.remove(&row.entity) {
+                Some((node, parts)) => {
+                    if previous_rows.get(&row.entity) != Some(&row) {
+                        ui::update_row(&mut commands, &theme, node, parts, row);
+                    }
+                    node
+                }
+                None => ui::spawn_row(&mut commands, &theme, row).id(),
+            };
+            ordered_nodes.push(node);
+        }
+        for (node, _) in row_nodes.into_values() {
+            commands.entity(node).despawn_recursive();
+        }
+        if !list_children.is_some_and(|children| **children == *ordered_nodes) {
+            commands.entity(list).replace_children(&ordered_nodes);
+        }
+    }
+    *displayed_rows = rows;
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Edit the name of the entity being renamed from the keyboard.
+///
+/// Enter applies the new [`Name`], Escape cancels. Focusing another text input cancels too.
+fn handle_rename_input(
+    mut commands: Commands,
+    mut state: ResMut<SceneTreeState>,
+    mut text_input_focus: ResMut<TextInputFocus>,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut changes: EventWriter<NewChange>,
+    names: Query<Option<&Name>>,
+) {
+    if state
+        .renaming
+        .as_ref()
+        .is_some_and(|rename| !text_input_focus.is_focused_by(rename.entity))
+    {
+        state.renaming = None;
+    }
+    if state.renaming.is_none() {
+        keyboard_events.clear();
+        return;
+    }
+
+    let mut apply = None;
+    let rename = state.renaming.as_mut().unwrap();
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Enter => apply = Some(true),
+            Key::Escape => apply = Some(false),
+            Key::Backspace => {
+                rename.text.pop();
+            }
+            Key::Space => rename.text.push(' '),
+            Key::Character(characters) => rename.text.push_str(characters),
+            _ => {}
+        }
+        if apply.is_some() {
+            break;
+        }
+    }
 
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+    let Some(apply) = apply else {
+        return;
+    };
+    let rename = state.renaming.take().unwrap();
+    text_input_focus.release(rename.entity);
+    if !apply {
+        return;
     }
+    let Ok(old_name) = names.get(rename.entity) else {
+        return;
+    };
+    let name = Name::new(rename.text);
+    changes.send(match old_name {
+        Some(old_name) => NewChange::new(ComponentChange::new(
+            rename.entity,
+            old_name.clone(),
+            name.clone(),
+        )),
+        None => NewChange::new(AddedComponent::new(rename.entity, name.clone())),
+    });
+    commands.entity(rename.entity).insert(name);
 }
@@ -0,0 +1,426 @@
+//! Module for all the UI components of the Scene Tree
+
+use bevy::prelude::*;
+use bevy_editor_core::{EditorSelection, Locked, TextInputFocus, DOUBLE_CLICK_DELAY};
+use bevy_editor_styles::Theme;
+use bevy_pane_layout::PaneContentNode;
+use bevy_scroll_box::spawn_scroll_box;
+use bevy_undo::{AddedComponent, ComponentChange, NewChange, RemovedComponent, ReparentedEntity};
+
+use crate::{Rename, SceneTreeState, TreeRow};
+
+/// Horizontal offset of a row for each level of depth in the hierarchy.
+const INDENT_WIDTH: f32 = 14.0;
+
+/// The root node for the scene tree.
+#[derive(Component)]
+pub struct SceneTreeNode;
+
+/// The node holding the rows of a scene tree pane.
+#[derive(Component)]
+pub(crate) struct SceneTreeList;
+
+/// A row of the scene tree, displaying the given entity.
+#[derive(Component)]
+pub(crate) struct SceneTreeRow(pub(crate) Entity);
+
+/// The nodes of a row of the scene tree that change with what it displays.
+#[derive(Component)]
+pub(crate) struct RowParts {
+    expander: Entity,
+    label: Entity,
+    visibility_toggle: Entity,
+    lock_toggle: Entity,
+}
+
+/// Spawn the scene tree once the pane is created
+pub(crate) fn on_pane_creation(
+    trigger: Trigger<OnAdd, SceneTreeNode>,
+    mut commands: Commands,
+    theme: Res<Theme>,
+    children_query: Query<&Children>,
+    content: Query<&PaneContentNode>,
+) {
+    let pane_root = trigger.entity();
+    let content_node = children_query
+        .iter_descendants(pane_root)
+        .find(|e| content.contains(*e))
+        .unwrap();
+
+    commands.entity(content_node).insert(Node {
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        display: Display::Flex,
+        flex_direction: FlexDirection::Column,
+        ..default()
+    });
+
+    spawn_scroll_box(
+        &mut commands,
+        &theme,
+        Overflow::scroll_y(),
+        Some(|commands: &mut Commands, content_list: Entity| {
+            commands
+                .entity(content_list)
+                .observe(on_background_click)
+                .observe(on_background_drop);
+            commands
+                .spawn((
+                    SceneTreeList,
+                    Node {
+                        width: Val::Percent(100.0),
+                        flex_direction: FlexDirection::Column,
+                        ..default()
+                    },
+                ))
+                .set_parent(content_list);
+        }),
+    )
+    .set_parent(content_node);
+}
+
+pub(crate) fn spawn_row<'a>(
+    commands: &'a mut Commands,
+    theme: &Theme,
+    row: &TreeRow,
+) -> EntityCommands<'a> {
+    let entity = row.entity;
+    let row_node = commands
+        .spawn(SceneTreeRow(entity))
+        .observe(on_row_click)
+        .observe(on_row_drop)
+        .id();
+
+    // Expand/collapse toggle
+    let expander = spawn_text(commands, theme, "", theme.text.low_priority)
+        .insert(Node {
+            width: Val::Px(10.0),
+            ..default()
+        })
+        .set_parent(row_node)
+        .observe(
+            move |mut trigger: Trigger<Pointer<Click>>, mut state: ResMut<SceneTreeState>| {
+                trigger.propagate(false);
+                if !state.collapsed.remove(&entity) {
+                    state.collapsed.insert(entity);
+                }
+            },
+        )
+        .id();
+
+    // Name
+    let label = spawn_text(commands, theme, "", theme.text.text_color)
+        .insert(Node {
+            flex_grow: 1.0,
+            ..default()
+        })
+        .set_parent(row_node)
+        .id();
+
+    // Visibility toggle
+    let visibility_toggle = spawn_text(commands, theme, "", theme.text.low_priority)
+        .set_parent(row_node)
+        .observe(
+            move |mut trigger: Trigger<Pointer<Click>>,
+                  mut commands: Commands,
+                  mut changes: EventWriter<NewChange>,
+                  visibility_query: Query<Option<&Visibility>>| {
+                trigger.propagate(false);
+                let Ok(old_visibility) = visibility_query.get(entity) else {
+                    return;
+                };
+                let visibility = if old_visibility == Some(&Visibility::Hidden) {
+                    Visibility::Inherited
+                } else {
+                    Visibility::Hidden
+                };
+                changes.send(match old_visibility {
+                    Some(old_visibility) => {
+                        NewChange::new(ComponentChange::new(entity, *old_visibility, visibility))
+                    }
+                    None => NewChange::new(AddedComponent::new(entity, visibility)),
+                });
+                commands.entity(entity).insert(visibility);
+            },
+        )
+        .id();
+
+    // Lock toggle
+    let lock_toggle = spawn_text(commands, theme, "", theme.text.low_priority)
+        .set_parent(row_node)
+        .observe(
+            move |mut trigger: Trigger<Pointer<Click>>,
+                  mut commands: Commands,
+                  mut changes: EventWriter<NewChange>,
+                  locked_query: Query<Has<Locked>>| {
+                trigger.propagate(false);
+                let Ok(locked) = locked_query.get(entity) else {
+                    return;
+                };
+                if locked {
+                    commands.entity(entity).remove::<Locked>();
+                    changes.send(NewChange::new(RemovedComponent::new(entity, Locked)));
+                } else {
+                    commands.entity(entity).insert(Locked);
+                    changes.send(NewChange::new(AddedComponent::new(entity, Locked)));
+                }
+            },
+        )
+        .id();
+
+    let parts = RowParts {
+        expander,
+        label,
+        visibility_toggle,
+        lock_toggle,
+    };
+    update_row(commands, theme, row_node, &parts, row);
+    commands.entity(row_node).insert(parts);
+    commands.entity(row_node)
+}
+
+/// Update the row node `row_node`, made of `parts`, to display `row`.
+pub(crate) fn update_row(
+    commands: &mut Commands,
+    theme: &Theme,
+    row_node: Entity,
+    parts: &RowParts,
+    row: &TreeRow,
+) {
+    commands.entity(row_node).insert((
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Px(20.0),
+            padding: UiRect::new(
+                Val::Px(4.0 + INDENT_WIDTH * row.depth as f32),
+                Val::Px(4.0),
+                Val::ZERO,
+                Val::ZERO,
+            ),
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(4.0),
+            ..default()
+        },
+        if row.selected {
+            theme.context_menu.hover_color
+        } else {
+            BackgroundColor(Color::NONE)
+        },
+    ));
+
+    commands.entity(parts.expander).insert((
+        Text::new(match row.expanded {
+            Some(true) => "v",
+            Some(false) => ">",
+            None => "",
+        }),
+        // Without children, clicking the toggle clicks the row
+        if row.expanded.is_some() {
+            PickingBehavior::default()
+        } else {
+            PickingBehavior::IGNORE
+        },
+    ));
+
+    commands.entity(parts.label).insert((
+        Text::new(row.label.clone()),
+        TextColor(if row.renaming {
+            theme.text.high_priority
+        } else if row.hidden {
+            theme.text.low_priority
+        } else {
+            theme.text.text_color
+        }),
+    ));
+
+    commands
+        .entity(parts.visibility_toggle)
+        .insert(Text::new(if row.hidden { "Show" } else { "Hide" }));
+    commands
+        .entity(parts.lock_toggle)
+        .insert(Text::new(if row.locked { "Unlock" } else { "Lock" }));
+}
+
+fn spawn_text<'a>(
+    commands: &'a mut Commands,
+    theme: &Theme,
+    text: &str,
+    color: Color,
+) -> EntityCommands<'a> {
+    commands.spawn((
+        Text::new(text),
+        TextFont {
+            font: theme.text.font.clone(),
+            font_size: 12.0,
+            ..default()
+        },
+        TextColor(color),
+    ))
+}
+
+/// Change the selection when a row is clicked, and start renaming its entity on double-click.
+fn on_row_click(
+    mut trigger: Trigger<Pointer<Click>>,
+    time: Res<Time<Real>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut selection: ResMut<EditorSelection>,
+    mut state: ResMut<SceneTreeState>,
+    mut text_input_focus: ResMut<TextInputFocus>,
+    row_query: Query<&SceneTreeRow>,
+    name_query: Query<&Name>,
+) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    trigger.propagate(false);
+    let Ok(&SceneTreeRow(entity)) = row_query.get(trigger.entity()) else {
+        return;
+    };
+
+    let ctrl = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if shift {
+        let anchor = state
+            .anchor
+            .filter(|anchor| state.rows.contains(anchor))
+            .unwrap_or(entity);
+        let position = |e: Entity| state.rows.iter().position(|row| *row == e);
+        let (Some(from), Some(to)) = (position(anchor), position(entity)) else {
+            return;
+        };
+        if !ctrl {
+            selection.clear();
+        }
+        selection.extend(
+            state.rows[from.min(to)..=from.max(to)]
+                .iter()
+                .copied()
+                .filter(|e| *e != entity),
+        );
+        // The clicked entity becomes the primary one
+        selection.add(entity);
+        return;
+    }
+
+    state.anchor = Some(entity);
+    if ctrl {
+        selection.toggle(entity);
+        return;
+    }
+
+    let now = time.elapsed();
+    let is_double_click = state
+        .last_click
+        .is_some_and(|(last, last_time)| last == entity && now - last_time <= DOUBLE_CLICK_DELAY);
+    if is_double_click {
+        // Reset so that a third click start a new double-click
+        state.last_click = None;
+        state.renaming = Some(Rename {
+            entity,
+            text: name_query
+                .get(entity)
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+        });
+        text_input_focus.0 = Some(entity);
+    } else {
+        state.last_click = Some((entity, now));
+    }
+    selection.set(entity);
+}
+
+/// Reparent the dropped rows to the entity of the row they are dropped on.
+fn on_row_drop(
+    mut trigger: Trigger<Pointer<DragDrop>>,
+    mut commands: Commands,
+    mut changes: EventWriter<NewChange>,
+    selection: Res<EditorSelection>,
+    row_query: Query<&SceneTreeRow>,
+    parent_query: Query<&Parent>,
+) {
+    let event = trigger.event();
+    if event.button != PointerButton::Primary {
+        return;
+    }
+    let Ok(&SceneTreeRow(dropped)) = row_query.get(event.dropped) else {
+        return;
+    };
+    trigger.propagate(false);
+    let Ok(&SceneTreeRow(new_parent)) = row_query.get(trigger.entity()) else {
+        return;
+    };
+
+    for entity in dragged_entities(dropped, &selection, &parent_query) {
+        // An entity can't become a child of itself or of one of its descendants
+        if entity == new_parent || parent_query.iter_ancestors(new_parent).any(|e| e == entity) {
+            continue;
+        }
+        let old_parent = parent_query.get(entity).ok().map(Parent::get);
+        if old_parent == Some(new_parent) {
+            continue;
+        }
+        commands.entity(entity).set_parent_in_place(new_parent);
+        changes.send(NewChange::new(ReparentedEntity {
+            entity,
+            old_parent,
+            new_parent: Some(new_parent),
+        }));
+    }
+}
+
+/// Deselect everything when clicking below the rows.
+fn on_background_click(trigger: Trigger<Pointer<Click>>, mut selection: ResMut<EditorSelection>) {
+    if trigger.event().button == PointerButton::Primary {
+        selection.clear();
+    }
+}
+
+/// Move the rows dropped below the rows to the root of the hierarchy.
+fn on_background_drop(
+    trigger: Trigger<Pointer<DragDrop>>,
+    mut commands: Commands,
+    mut changes: EventWriter<NewChange>,
+    selection: Res<EditorSelection>,
+    row_query: Query<&SceneTreeRow>,
+    parent_query: Query<&Parent>,
+) {
+    let event = trigger.event();
+    if event.button != PointerButton::Primary {
+        return;
+    }
+    let Ok(&SceneTreeRow(dropped)) = row_query.get(event.dropped) else {
+        return;
+    };
+
+    for entity in dragged_entities(dropped, &selection, &parent_query) {
+        let Ok(old_parent) = parent_query.get(entity) else {
+            continue;
+        };
+        commands.entity(entity).remove_parent_in_place();
+        changes.send(NewChange::new(ReparentedEntity {
+            entity,
+            old_parent: Some(old_parent.get()),
+            new_parent: None,
+        }));
+    }
+}
+
+/// The entities moved when the row of `dropped` is dragged: the whole selection if the entity is
+/// part of it, without the entities whose ancestor is also moved so the selected sub-trees are kept.
+fn dragged_entities(
+    dropped: Entity,
+    selection: &EditorSelection,
+    parent_query: &Query<&Parent>,
+) -> Vec<Entity> {
+    if !selection.contains(dropped) {
+        return vec![dropped];
+    }
+    selection
+        .iter()
+        .filter(|entity| {
+            !parent_query
+                .iter_ancestors(*entity)
+                .any(|ancestor| selection.contains(ancestor))
+        })
+        .collect()
+}
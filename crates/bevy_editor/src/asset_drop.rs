@@ -0,0 +1,361 @@
+//! Drag-and-drop of scenes from the asset browser into the 3D viewport.
+//!
+//! While a scene file is dragged over a viewport, the scene is spawned under the pointer as a ghost
+//! preview. It sticks to the bounding box of the geometry under the pointer. When there is none, it
+//! sticks to the ground plane defined by [`AssetDropSettings`], or to the plane through the origin
+//! facing the camera in the orthographic views. Releasing the pointer keeps the scene where it is,
+//! leaving the viewport despawns it. Dropping a scene can be undone. Other files, meshes included,
+//! can't be dropped: the cursor shows it while they are dragged over a viewport.
+
+use std::sync::Arc;
+
+use bevy::{
+    asset::AssetPath,
+    ecs::system::SystemParam,
+    picking::pointer::Location,
+    prelude::*,
+    render::{camera::NormalizedRenderTarget, primitives::Aabb},
+    scene::SceneInstanceReady,
+    utils::HashMap,
+    window::{PrimaryWindow, SystemCursorIcon},
+    winit::cursor::CursorIcon,
+};
+use bevy_3d_viewport::Bevy3dViewport;
+use bevy_asset_browser::AssetBrowserFile;
+use bevy_editor_core::{
+    raycast::{pointer_ray, ray_aabb_distance},
+    EditorEntity,
+};
+use bevy_pane_layout::PaneContentNode;
+use bevy_undo::{get_entity_with_remap, ChangeResult, EditorChange, NewChange};
+
+pub(crate) struct AssetDropPlugin;
+
+impl Plugin for AssetDropPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AssetDropSettings>()
+            .init_resource::<DropGhost>()
+            .add_observer(
+                |trigger: Trigger<OnAdd, Bevy3dViewport>, mut commands: Commands| {
+                    commands
+                        .entity(trigger.entity())
+                        .observe(on_drag_over)
+                        .observe(on_drag_leave)
+                        .observe(on_drag_drop);
+                },
+            );
+    }
+}
+
+/// Settings used when dropping assets from the asset browser into a 3D viewport.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct AssetDropSettings {
+    /// Height of the horizontal plane scenes are dropped on when the pointer isn't over any geometry.
+    ///
+    /// The orthographic views drop them on the plane through the origin facing their camera instead.
+    pub ground_plane_height: f32,
+}
+
+impl Default for AssetDropSettings {
+    fn default() -> Self {
+        Self {
+            ground_plane_height: 0.0,
+        }
+    }
+}
+
+/// The scene currently following the pointer while it is dragged over a viewport.
+///
+/// The ghost and the entities spawned from its scene are [`EditorEntity`]s until it is dropped, so
+/// they aren't saved nor listed in the scene tree.
+#[derive(Resource, Default)]
+struct DropGhost {
+    entity: Option<Entity>,
+    /// Whether the dragged file isn't a scene, so it can't be dropped.
+    rejected: bool,
+}
+
+/// The root component to spawn for a dropped scene file, depending on its format.
+#[derive(Clone)]
+enum DroppedScene {
+    Scene(SceneRoot),
+    Dynamic(DynamicSceneRoot),
+}
+
+impl DroppedScene {
+    /// Load the scene pointed to by `path`, or `None` if the file isn't a scene.
+    fn load(path: &AssetPath<'static>, asset_server: &AssetServer) -> Option<Self> {
+        let extension = path.get_full_extension()?.to_lowercase();
+        match extension.as_str() {
+            "gltf" | "glb" => Some(Self::Scene(SceneRoot(
+                asset_server.load(GltfAssetLabel::Scene(0).from_asset(path.clone())),
+            ))),
+            "scn" | "scn.ron" => Some(Self::Dynamic(DynamicSceneRoot(
+                asset_server.load(path.clone()),
+            ))),
+            _ => None,
+        }
+    }
+
+    fn spawn(self, commands: &mut Commands, transform: Transform) -> Entity {
+        match self {
+            Self::Scene(root) => commands.spawn((root, transform)).id(),
+            Self::Dynamic(root) => commands.spawn((root, transform)).id(),
+        }
+    }
+
+    fn spawn_in_world(&self, world: &mut World, transform: Transform) -> Entity {
+        match self {
+            Self::Scene(root) => world.spawn((root.clone(), transform)).id(),
+            Self::Dynamic(root) => world.spawn((root.clone(), transform)).id(),
+        }
+    }
+}
+
+/// The drop of a scene into a viewport.
+///
+/// Undoing it despawns the root of the scene along with the entities spawned from the scene, and
+/// redoing it spawns the root again, which spawns the scene anew.
+struct SceneDrop {
+    entity: Entity,
+    scene: DroppedScene,
+    transform: Transform,
+    /// Whether reverting this change despawns the scene, `false` for its inverse.
+    dropped: bool,
+}
+
+impl EditorChange for SceneDrop {
+    fn revert(
+        &self,
+        world: &mut World,
+        entity_remap: &HashMap<Entity, Entity>,
+    ) -> Result<ChangeResult, String> {
+        if self.dropped {
+            let entity = get_entity_with_remap(self.entity, entity_remap);
+            if let Ok(entity) = world.get_entity_mut(entity) {
+                entity.despawn_recursive();
+            }
+            Ok(ChangeResult::Success)
+        } else {
+            let entity = self.scene.spawn_in_world(world, self.transform);
+            Ok(ChangeResult::SuccessWithRemap(vec![(self.entity, entity)]))
+        }
+    }
+
+    fn debug_text(&self) -> String {
+        if self.dropped {
+            format!("Dropped Scene: {}", self.entity.index())
+        } else {
+            format!("Removed Dropped Scene: {}", self.entity.index())
+        }
+    }
+
+    fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
+        Arc::new(SceneDrop {
+            entity: self.entity,
+            scene: self.scene.clone(),
+            transform: self.transform,
+            dropped: !self.dropped,
+        })
+    }
+}
+
+/// Everything needed to find the world position under the pointer in a 3D viewport.
+#[derive(SystemParam)]
+struct DropTarget<'w, 's> {
+    settings: Res<'w, AssetDropSettings>,
+    viewports: Query<'w, 's, &'static Bevy3dViewport>,
+    children: Query<'w, 's, &'static Children>,
+    parents: Query<'w, 's, &'static Parent>,
+    content: Query<'w, 's, &'static PaneContentNode>,
+    nodes: Query<'w, 's, (&'static ComputedNode, &'static GlobalTransform)>,
+    cameras: Query<'w, 's, (&'static Camera, &'static GlobalTransform)>,
+    meshes: Query<'w, 's, (Entity, &'static Aabb, &'static GlobalTransform), With<Mesh3d>>,
+}
+
+impl DropTarget<'_, '_> {
+    /// Compute the world position under the pointer when it is over the render-target image of
+    /// the viewport `pane_root`. The `ghost` hierarchy is ignored so it doesn't land on itself.
+    fn position(
+        &self,
+        pane_root: Entity,
+        target: Entity,
+        location: &Location,
+        ghost: Option<Entity>,
+    ) -> Option<Vec3> {
+        let viewport = self.viewports.get(pane_root).ok()?;
+        let content_node = self
+            .children
+            .iter_descendants(pane_root)
+            .find(|e| self.content.contains(*e))?;
+        let image = *self.children.get(content_node).ok()?.first()?;
+        if target != image || !matches!(location.target, NormalizedRenderTarget::Window(..)) {
+            return None;
+        }
+
+        let (computed_node, node_transform) = self.nodes.get(image).ok()?;
+        let node_rect =
+            Rect::from_center_size(node_transform.translation().xy(), computed_node.size());
+        let (camera, camera_transform) = self.cameras.get(viewport.camera()).ok()?;
+        let ray = pointer_ray(camera, camera_transform, location.position - node_rect.min)?;
+
+        let is_ghost = |entity: Entity| {
+            ghost.is_some_and(|ghost| {
+                entity == ghost || self.parents.iter_ancestors(entity).any(|e| e == ghost)
+            })
+        };
+        let distance = self
+            .meshes
+            .iter()
+            .filter(|(entity, ..)| !is_ghost(*entity))
+            .filter_map(|(_, aabb, transform)| ray_aabb_distance(ray, aabb, transform))
+            .min_by(f32::total_cmp)
+            .or_else(|| {
+                if viewport.view().is_orthographic() {
+                    // The front and side views see the ground plane edge-on
+                    ray.intersect_plane(Vec3::ZERO, InfinitePlane3d::new(camera_transform.back()))
+                } else {
+                    ray.intersect_plane(
+                        Vec3::Y * self.settings.ground_plane_height,
+                        InfinitePlane3d::new(Vec3::Y),
+                    )
+                }
+            })?;
+        Some(ray.get_point(distance))
+    }
+}
+
+/// Move the ghost under the pointer, spawning it the first time the position under the pointer is found.
+///
+/// Files that aren't scenes show the not-allowed cursor instead.
+#[allow(clippy::too_many_arguments)]
+fn on_drag_over(
+    trigger: Trigger<Pointer<DragOver>>,
+    mut commands: Commands,
+    mut ghost: ResMut<DropGhost>,
+    asset_server: Res<AssetServer>,
+    files: Query<&AssetBrowserFile>,
+    windows: Query<Entity, With<PrimaryWindow>>,
+    mut transforms: Query<&mut Transform>,
+    drop_target: DropTarget,
+) {
+    let event = trigger.event();
+    if event.button != PointerButton::Primary || ghost.rejected {
+        return;
+    }
+    let Some(position) = drop_target.position(
+        trigger.entity(),
+        event.target,
+        &event.pointer_location,
+        ghost.entity,
+    ) else {
+        return;
+    };
+
+    if let Some(ghost) = ghost.entity {
+        if let Ok(mut transform) = transforms.get_mut(ghost) {
+            transform.translation = position;
+        }
+        return;
+    }
+    let Ok(file) = files.get(event.dragged) else {
+        return;
+    };
+    let Some(scene) = DroppedScene::load(&file.0, &asset_server) else {
+        ghost.rejected = true;
+        if let Ok(window) = windows.get_single() {
+            commands
+                .entity(window)
+                .insert(CursorIcon::System(SystemCursorIcon::NotAllowed));
+        }
+        return;
+    };
+    let entity = scene.spawn(&mut commands, Transform::from_translation(position));
+    commands
+        .entity(entity)
+        .insert(EditorEntity)
+        .observe(tag_ghost_scene);
+    ghost.entity = Some(entity);
+}
+
+/// Make the entities spawned from the scene of the ghost [`EditorEntity`]s too, unless it has been
+/// dropped in the meantime.
+fn tag_ghost_scene(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    ghost: Res<DropGhost>,
+    children: Query<&Children>,
+) {
+    if ghost.entity != Some(trigger.entity()) {
+        return;
+    }
+    for entity in children.iter_descendants(trigger.entity()) {
+        commands.entity(entity).insert(EditorEntity);
+    }
+}
+
+fn on_drag_leave(
+    _trigger: Trigger<Pointer<DragLeave>>,
+    mut commands: Commands,
+    mut ghost: ResMut<DropGhost>,
+    windows: Query<Entity, With<PrimaryWindow>>,
+) {
+    // A dropped scene has already been taken out of the ghost by `on_drag_drop`
+    if let Some(entity) = ghost.entity.take() {
+        commands.entity(entity).despawn_recursive();
+    }
+    if std::mem::take(&mut ghost.rejected) {
+        if let Ok(window) = windows.get_single() {
+            commands
+                .entity(window)
+                .insert(CursorIcon::System(SystemCursorIcon::Default));
+        }
+    }
+}
+
+fn on_drag_drop(
+    trigger: Trigger<Pointer<DragDrop>>,
+    mut commands: Commands,
+    mut ghost: ResMut<DropGhost>,
+    mut changes: EventWriter<NewChange>,
+    files: Query<&AssetBrowserFile>,
+    children: Query<&Children>,
+    roots: Query<(&Transform, Option<&SceneRoot>, Option<&DynamicSceneRoot>)>,
+) {
+    let event = trigger.event();
+    if event.button != PointerButton::Primary {
+        return;
+    }
+    if ghost.rejected {
+        if let Ok(file) = files.get(event.dropped) {
+            warn!(
+                "Only scene files can be dropped into the viewport, {} isn't one",
+                file.0
+            );
+        }
+        return;
+    }
+    // The ghost is already in place, keep it as the dropped scene
+    let Some(entity) = ghost.entity.take() else {
+        return;
+    };
+    commands.entity(entity).remove::<EditorEntity>();
+    for descendant in children.iter_descendants(entity) {
+        commands.entity(descendant).remove::<EditorEntity>();
+    }
+
+    let Ok((transform, scene_root, dynamic_scene_root)) = roots.get(entity) else {
+        return;
+    };
+    let scene = match (scene_root, dynamic_scene_root) {
+        (Some(root), _) => DroppedScene::Scene(root.clone()),
+        (None, Some(root)) => DroppedScene::Dynamic(root.clone()),
+        (None, None) => return,
+    };
+    changes.send(NewChange::new(SceneDrop {
+        entity,
+        scene,
+        transform: *transform,
+        dropped: true,
+    }));
+}
@@ -17,17 +17,28 @@ use bevy::prelude::*;
 pub use bevy;
 
 use bevy_context_menu::ContextMenuPlugin;
+use bevy_editor_core::EditorCorePlugin;
 use bevy_editor_styles::StylesPlugin;
+use bevy_transform_gizmos::TransformGizmoPlugin;
+use bevy_undo::{UndoPlugin, UndoRedoKeybindsPlugin};
 
 // Panes
 use bevy_2d_viewport::Viewport2dPanePlugin;
 use bevy_3d_viewport::Viewport3dPanePlugin;
 use bevy_asset_browser::AssetBrowserPanePlugin;
+use bevy_properties_pane::PropertiesPanePlugin;
+use bevy_scene_tree::SceneTreePlugin;
 
-use crate::load_gltf::LoadGltfPlugin;
+use crate::{
+    asset_drop::AssetDropPlugin, load_gltf::LoadGltfPlugin, play_mode::PlayModePlugin,
+    scene_file::SceneFilePlugin,
+};
 
+pub mod asset_drop;
 mod load_gltf;
+pub mod play_mode;
 pub mod project;
+pub mod scene_file;
 mod ui;
 
 /// The plugin that handle the bare minimum to run the application
@@ -49,13 +60,22 @@ impl Plugin for EditorPlugin {
 
         bevy_app
             .add_plugins((
+                EditorCorePlugin,
+                UndoPlugin,
+                UndoRedoKeybindsPlugin,
                 ContextMenuPlugin,
                 StylesPlugin,
+                TransformGizmoPlugin,
                 Viewport2dPanePlugin,
                 Viewport3dPanePlugin,
                 ui::EditorUIPlugin,
                 AssetBrowserPanePlugin,
+                SceneTreePlugin,
+                PropertiesPanePlugin,
                 LoadGltfPlugin,
+                SceneFilePlugin,
+                AssetDropPlugin,
+                PlayModePlugin,
             ))
             .add_systems(Startup, dummy_setup);
     }
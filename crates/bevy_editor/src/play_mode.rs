@@ -0,0 +1,447 @@
+//! Running the game inside the editor, and going back to the authored scene afterwards.
+//!
+//! Entering play mode takes a snapshot of the edited world, stopping restores it, so whatever the
+//! game systems did to the scene while playing is undone. The entities despawned while playing are
+//! spawned again under new ids, which the selection and the undo history follow.
+//!
+//! - Ctrl+P starts playing, or stops if the game is already running.
+//! - Ctrl+Shift+P pauses or resumes the game.
+//!
+//! Game systems only run while playing when they are added with
+//! `.run_if(in_state(PlayState::Playing))`.
+
+use std::any::TypeId;
+
+use bevy::{
+    ecs::entity::{EntityHashMap, EntityHashSet},
+    prelude::*,
+    reflect::TypeInfo,
+};
+use bevy_editor_core::{EditedEntityFilter, EditorSelection};
+use bevy_editor_styles::Theme;
+use bevy_menu_bar::{MenuBarNode, MenuBarSet};
+use bevy_undo::ChangeChain;
+
+use crate::scene_file::extract_edited_world;
+
+pub(crate) struct PlayModePlugin;
+
+impl Plugin for PlayModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<PlayState>()
+            .init_resource::<PlayModeSnapshot>()
+            .add_systems(Startup, toolbar_setup.after(MenuBarSet))
+            .add_systems(Update, (play_mode_shortcuts, update_toolbar_buttons))
+            .add_systems(
+                OnTransition {
+                    exited: PlayState::Editing,
+                    entered: PlayState::Playing,
+                },
+                take_snapshot,
+            )
+            .add_systems(OnEnter(PlayState::Editing), restore_snapshot);
+    }
+}
+
+/// Whether the game is running inside the editor.
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlayState {
+    /// The authored scene is being edited, the game systems don't run.
+    #[default]
+    Editing,
+    /// The game systems are running on a copy of the authored scene.
+    Playing,
+    /// The game is stopped where it is, but the authored scene isn't restored yet.
+    Paused,
+}
+
+/// The edited world, as it was when the game started playing.
+#[derive(Resource, Default)]
+struct PlayModeSnapshot(Option<Snapshot>);
+
+struct Snapshot {
+    scene: DynamicScene,
+    /// The entities selected when the game started playing.
+    selection: Vec<Entity>,
+}
+
+fn take_snapshot(world: &mut World) {
+    let scene = extract_edited_world(world);
+    let selection = world
+        .get_resource::<EditorSelection>()
+        .map(|selection| selection.iter().collect())
+        .unwrap_or_default();
+    world.resource_mut::<PlayModeSnapshot>().0 = Some(Snapshot { scene, selection });
+}
+
+/// Put the edited world back the way it was in the snapshot.
+///
+/// The entities that still exist keep their identity, so the selection and the rest of the editor
+/// state stay valid. The despawned ones are spawned again under new ids: the ones that were
+/// selected when the game started are selected again, and the undo history is remapped to them.
+/// Only reflected components are restored, changes to the other ones survive.
+fn restore_snapshot(world: &mut World) {
+    let Some(Snapshot {
+        scene: snapshot,
+        selection: snapshot_selection,
+    }) = world.resource_mut::<PlayModeSnapshot>().0.take()
+    else {
+        return;
+    };
+
+    // Overwrite the components of the entities that still exist, and respawn the despawned ones
+    let mut entity_map: EntityHashMap<Entity> = snapshot
+        .entities
+        .iter()
+        .map(|scene_entity| scene_entity.entity)
+        .filter(|entity| world.entities().contains(*entity))
+        .map(|entity| (entity, entity))
+        .collect();
+    if let Err(error) = snapshot.write_to_world(world, &mut entity_map) {
+        error!("Failed to restore the scene after playing: {error}");
+        return;
+    }
+
+    let respawned: EntityHashMap<Entity> = entity_map
+        .iter()
+        .filter(|(entity, new_entity)| entity != new_entity)
+        .map(|(entity, new_entity)| (*entity, *new_entity))
+        .collect();
+    if let Some(mut selection) = world.get_resource_mut::<EditorSelection>() {
+        for entity in snapshot_selection {
+            if let Some(&new_entity) = respawned.get(&entity) {
+                selection.add(new_entity);
+            }
+        }
+    }
+    if let Some(mut change_chain) = world.get_resource_mut::<ChangeChain>() {
+        for (&entity, &new_entity) in &respawned {
+            change_chain.remap_entity(entity, new_entity);
+        }
+    }
+
+    // Remove the components that were added while playing
+    let computed = [
+        TypeId::of::<GlobalTransform>(),
+        TypeId::of::<InheritedVisibility>(),
+        TypeId::of::<ViewVisibility>(),
+    ];
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    for scene_entity in &snapshot.entities {
+        let Some(&entity) = entity_map.get(&scene_entity.entity) else {
+            continue;
+        };
+        let restored: Vec<TypeId> = scene_entity
+            .components
+            .iter()
+            .filter_map(|component| component.get_represented_type_info())
+            .map(TypeInfo::type_id)
+            .collect();
+        let mut added = Vec::new();
+        for info in world.inspect_entity(entity) {
+            let Some(type_id) = info.type_id() else {
+                continue;
+            };
+            if restored.contains(&type_id) || computed.contains(&type_id) {
+                continue;
+            }
+            if let Some(reflect_component) =
+                type_registry.get_type_data::<ReflectComponent>(type_id)
+            {
+                added.push(reflect_component.clone());
+            }
+        }
+        let mut entity_mut = world.entity_mut(entity);
+        for reflect_component in added {
+            reflect_component.remove(&mut entity_mut);
+        }
+    }
+
+    // Despawn the entities spawned while playing
+    let restored_entities: EntityHashSet = entity_map.values().copied().collect();
+    let spawned: Vec<Entity> = world
+        .query_filtered::<Entity, EditedEntityFilter>()
+        .iter(world)
+        .filter(|entity| !restored_entities.contains(entity))
+        .collect();
+    for entity in spawned {
+        despawn_spawned(world, entity, &restored_entities);
+    }
+}
+
+/// Despawn `entity` and its descendants, except the ones from the snapshot that were moved under it.
+fn despawn_spawned(world: &mut World, entity: Entity, restored_entities: &EntityHashSet) {
+    if !world.entities().contains(entity) {
+        return;
+    }
+    let children = world
+        .get::<Children>(entity)
+        .map(|children| children.to_vec())
+        .unwrap_or_default();
+    for child in children {
+        if !restored_entities.contains(&child) {
+            despawn_spawned(world, child, restored_entities);
+        }
+    }
+    // The hierarchy of the snapshot entities has already been restored, don't touch it
+    world.despawn(entity);
+}
+
+fn play_mode_shortcuts(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    state: Res<State<PlayState>>,
+    mut next_state: ResMut<NextState<PlayState>>,
+) {
+    if !keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        || !keyboard_input.just_pressed(KeyCode::KeyP)
+    {
+        return;
+    }
+    let pause = keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let action = match (pause, state.get()) {
+        (false, PlayState::Editing) => ToolbarAction::Play,
+        (false, _) => ToolbarAction::Stop,
+        (true, _) => ToolbarAction::Pause,
+    };
+    if let Some(next) = action.next_state(*state.get()) {
+        next_state.set(next);
+    }
+}
+
+/// A button of the play mode toolbar.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum ToolbarAction {
+    Play,
+    Pause,
+    Stop,
+}
+
+impl ToolbarAction {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Play => "Play",
+            Self::Pause => "Pause",
+            Self::Stop => "Stop",
+        }
+    }
+
+    /// The state to go to when the button is pressed in `state`, if it does anything.
+    fn next_state(self, state: PlayState) -> Option<PlayState> {
+        match (self, state) {
+            (Self::Play, PlayState::Editing | PlayState::Paused)
+            | (Self::Pause, PlayState::Paused) => Some(PlayState::Playing),
+            (Self::Pause, PlayState::Playing) => Some(PlayState::Paused),
+            (Self::Stop, PlayState::Playing | PlayState::Paused) => Some(PlayState::Editing),
+            _ => None,
+        }
+    }
+
+    /// Whether the button shows the current state.
+    fn is_active(self, state: PlayState) -> bool {
+        matches!(
+            (self, state),
+            (Self::Play, PlayState::Playing) | (Self::Pause, PlayState::Paused)
+        )
+    }
+}
+
+/// Add the play, pause and stop buttons in the middle of the menu bar.
+fn toolbar_setup(
+    mut commands: Commands,
+    root: Query<Entity, With<MenuBarNode>>,
+    theme: Res<Theme>,
+) {
+    let Ok(root) = root.get_single() else {
+        return;
+    };
+    let toolbar = commands
+        .spawn(Node {
+            margin: UiRect::horizontal(Val::Auto),
+            column_gap: Val::Px(2.0),
+            ..default()
+        })
+        .set_parent(root)
+        .id();
+
+    for action in [
+        ToolbarAction::Play,
+        ToolbarAction::Pause,
+        ToolbarAction::Stop,
+    ] {
+        commands
+            .spawn((
+                Node {
+                    padding: UiRect::axes(Val::Px(5.0), Val::Px(2.0)),
+                    ..default()
+                },
+                BorderRadius::all(Val::Px(3.)),
+                BackgroundColor(theme.menu.background_color),
+                action,
+            ))
+            .set_parent(toolbar)
+            .with_child((
+                Text::new(action.label()),
+                TextFont {
+                    font: theme.text.font.clone(),
+                    font_size: 12.,
+                    ..default()
+                },
+                TextColor(theme.text.low_priority),
+                PickingBehavior::IGNORE,
+            ))
+            .observe(
+                move |_trigger: Trigger<Pointer<Click>>,
+                      state: Res<State<PlayState>>,
+                      mut next_state: ResMut<NextState<PlayState>>| {
+                    if let Some(next) = action.next_state(*state.get()) {
+                        next_state.set(next);
+                    }
+                },
+            )
+            .observe(
+                |trigger: Trigger<Pointer<Over>>,
+                 theme: Res<Theme>,
+                 mut query: Query<&mut BackgroundColor>| {
+                    if let Ok(mut background) = query.get_mut(trigger.entity()) {
+                        background.0 = theme.button.hover_color;
+                    }
+                },
+            )
+            .observe(
+                |trigger: Trigger<Pointer<Out>>,
+                 theme: Res<Theme>,
+                 mut query: Query<&mut BackgroundColor>| {
+                    if let Ok(mut background) = query.get_mut(trigger.entity()) {
+                        background.0 = theme.menu.background_color;
+                    }
+                },
+            );
+    }
+}
+
+/// Highlight the button of the current state, and dim the buttons that can't be pressed.
+fn update_toolbar_buttons(
+    state: Res<State<PlayState>>,
+    theme: Res<Theme>,
+    buttons: Query<(&ToolbarAction, &Children)>,
+    mut text_colors: Query<&mut TextColor>,
+) {
+    if !state.is_changed() {
+        return;
+    }
+    let state = *state.get();
+    for (action, children) in &buttons {
+        let color = if action.is_active(state) {
+            theme.text.high_priority
+        } else if action.next_state(state).is_some() {
+            theme.text.text_color
+        } else {
+            theme.text.low_priority
+        };
+        for child in children {
+            if let Ok(mut text_color) = text_colors.get_mut(*child) {
+                text_color.0 = color;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edited_world() -> World {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world.init_resource::<PlayModeSnapshot>();
+        {
+            let mut type_registry = world.resource::<AppTypeRegistry>().write();
+            type_registry.register::<Transform>();
+            type_registry.register::<Name>();
+        }
+        world
+    }
+
+    #[test]
+    fn test_restore_changed_components() {
+        let mut world = edited_world();
+        let entity = world.spawn(Transform::from_xyz(1.0, 2.0, 3.0)).id();
+
+        take_snapshot(&mut world);
+        world.get_mut::<Transform>(entity).unwrap().translation = Vec3::ZERO;
+        restore_snapshot(&mut world);
+
+        assert_eq!(
+            world.get::<Transform>(entity),
+            Some(&Transform::from_xyz(1.0, 2.0, 3.0))
+        );
+    }
+
+    #[test]
+    fn test_respawn_despawned_entities() {
+        let mut world = edited_world();
+        let entity = world
+            .spawn((Name::new("Player"), Transform::default()))
+            .id();
+
+        take_snapshot(&mut world);
+        world.despawn(entity);
+        restore_snapshot(&mut world);
+
+        let names: Vec<String> = world
+            .query_filtered::<&Name, With<Transform>>()
+            .iter(&world)
+            .map(|name| name.as_str().to_string())
+            .collect();
+        assert_eq!(names, vec!["Player".to_string()]);
+    }
+
+    #[test]
+    fn test_select_respawned_entities() {
+        let mut world = edited_world();
+        world.init_resource::<EditorSelection>();
+        let entity = world.spawn(Transform::default()).id();
+        world.resource_mut::<EditorSelection>().set(entity);
+
+        take_snapshot(&mut world);
+        world.despawn(entity);
+        world.resource_mut::<EditorSelection>().clear();
+        restore_snapshot(&mut world);
+
+        let respawned = world
+            .query_filtered::<Entity, With<Transform>>()
+            .single(&world);
+        assert_ne!(respawned, entity);
+        assert_eq!(
+            world.resource::<EditorSelection>().primary(),
+            Some(respawned)
+        );
+    }
+
+    #[test]
+    fn test_remove_components_added_while_playing() {
+        let mut world = edited_world();
+        let entity = world.spawn(Transform::default()).id();
+
+        take_snapshot(&mut world);
+        world.entity_mut(entity).insert(Name::new("Added"));
+        restore_snapshot(&mut world);
+
+        assert!(world.get::<Transform>(entity).is_some());
+        assert!(world.get::<Name>(entity).is_none());
+    }
+
+    #[test]
+    fn test_despawn_entities_spawned_while_playing() {
+        let mut world = edited_world();
+        let entity = world.spawn(Transform::default()).id();
+
+        take_snapshot(&mut world);
+        let spawned = world.spawn(Transform::default()).id();
+        restore_snapshot(&mut world);
+
+        assert!(world.entities().contains(entity));
+        assert!(!world.entities().contains(spawned));
+    }
+}
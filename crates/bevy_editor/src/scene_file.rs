@@ -0,0 +1,584 @@
+//! Saving the edited world to a `.scn.ron` scene file, and opening it back.
+//!
+//! - Ctrl+S saves to the current scene file, or asks for one if there is none yet.
+//! - Ctrl+Shift+S asks for a new scene file to save to.
+//! - Ctrl+O replaces the edited world with the content of a scene file.
+//! - Double-clicking a scene file in the asset browser opens it too.
+//!
+//! Asset handles can't be saved, so the meshes, materials and scenes of the entities are saved
+//! as [`SceneAssetPaths`] instead, and loaded back when the scene file is opened.
+
+use std::{any::TypeId, collections::BTreeSet, fs::File, io::Write, path::PathBuf};
+
+use bevy::{
+    asset::io::AssetSourceId,
+    ecs::entity::{EntityHashMap, EntityHashSet},
+    prelude::*,
+    reflect::{serde::TypedReflectSerializer, ReflectMut, TypeInfo, TypeRegistry},
+    scene::{serde::SceneDeserializer, DynamicEntity, InstanceId, SceneInstance},
+    tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, IoTaskPool, Task},
+};
+use bevy_asset_browser::{AssetDoubleClicked, DefaultSourceFilePath};
+use bevy_editor_core::{EditedEntityFilter, EditorSelection};
+use rfd::{AsyncFileDialog, FileHandle};
+use serde::de::DeserializeSeed;
+
+use crate::play_mode::PlayState;
+
+pub(crate) struct SceneFilePlugin;
+
+impl Plugin for SceneFilePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<SceneAssetPaths>()
+            .init_resource::<EditedScenePath>()
+            .init_resource::<SceneFileDialogTask>()
+            .init_resource::<SceneSaveTasks>()
+            .init_resource::<OpeningScene>()
+            .add_observer(load_scene_asset_paths)
+            .add_systems(
+                Update,
+                (
+                    // The world is only saved or replaced while it holds the authored scene. A
+                    // dialog closed while playing is handled once the game stops.
+                    (
+                        scene_file_shortcuts,
+                        poll_scene_file_dialog,
+                        open_double_clicked_scene,
+                        spawn_opened_scene,
+                    )
+                        .run_if(in_state(PlayState::Editing)),
+                    poll_scene_saves,
+                ),
+            );
+    }
+}
+
+/// The scene file the edited world is saved to.
+///
+/// It is only changed once a scene file has been written or opened successfully.
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq)]
+pub struct EditedScenePath(pub Option<PathBuf>);
+
+/// The paths of the assets of an entity, saved in scene files in place of their handles.
+///
+/// When an entity with this component is spawned, the assets are loaded back into their
+/// components and this component is removed.
+#[derive(Component, Reflect, Default, Debug, Clone, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub struct SceneAssetPaths {
+    /// The asset of the [`Mesh3d`].
+    pub mesh: Option<String>,
+    /// The asset of the [`MeshMaterial3d<StandardMaterial>`].
+    pub material: Option<String>,
+    /// The asset of the [`SceneRoot`].
+    pub scene: Option<String>,
+}
+
+impl SceneAssetPaths {
+    fn is_empty(&self) -> bool {
+        self.mesh.is_none() && self.material.is_none() && self.scene.is_none()
+    }
+}
+
+/// The scene files being written, with their path.
+#[derive(Resource, Default)]
+struct SceneSaveTasks(Vec<(PathBuf, Task<std::io::Result<()>>)>);
+
+/// The scene file being read, with its path. It replaces the edited world once it is read.
+#[derive(Resource, Default)]
+struct OpeningScene(Option<(PathBuf, Task<Result<DynamicScene, String>>)>);
+
+enum SceneFileDialog {
+    Save,
+    Open,
+}
+
+#[derive(Resource, Default)]
+struct SceneFileDialogTask(Option<(SceneFileDialog, Task<Option<FileHandle>>)>);
+
+fn scene_file_shortcuts(
+    mut commands: Commands,
+    mut dialog_task: ResMut<SceneFileDialogTask>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    scene_path: Res<EditedScenePath>,
+) {
+    if dialog_task.0.is_some()
+        || !keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+    {
+        return;
+    }
+    let save_as = keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
+    if keyboard_input.just_pressed(KeyCode::KeyS) {
+        match &scene_path.0 {
+            Some(path) if !save_as => {
+                let path = path.clone();
+                commands.queue(move |world: &mut World| save_scene(world, path));
+            }
+            _ => {
+                dialog_task.0 = Some((
+                    SceneFileDialog::Save,
+                    AsyncComputeTaskPool::get().spawn(
+                        AsyncFileDialog::new()
+                            .set_title("Save scene")
+                            .add_filter("Bevy scene", &["ron"])
+                            .set_file_name("scene.scn.ron")
+                            .save_file(),
+                    ),
+                ));
+            }
+        }
+    } else if keyboard_input.just_pressed(KeyCode::KeyO) {
+        dialog_task.0 = Some((
+            SceneFileDialog::Open,
+            AsyncComputeTaskPool::get().spawn(
+                AsyncFileDialog::new()
+                    .set_title("Open scene")
+                    .add_filter("Bevy scene", &["ron"])
+                    .pick_file(),
+            ),
+        ));
+    }
+}
+
+fn poll_scene_file_dialog(mut commands: Commands, mut dialog_task: ResMut<SceneFileDialogTask>) {
+    let Some((_, task)) = &mut dialog_task.0 else {
+        return;
+    };
+
+    let Some(result) = block_on(future::poll_once(task)) else {
+        return;
+    };
+    let Some((dialog, _)) = dialog_task.0.take() else {
+        return;
+    };
+
+    let Some(file) = result else {
+        return;
+    };
+    let path = file.path().to_owned();
+    match dialog {
+        SceneFileDialog::Save => {
+            let path = with_scene_extension(path);
+            commands.queue(move |world: &mut World| save_scene(world, path));
+        }
+        SceneFileDialog::Open => {
+            commands.queue(move |world: &mut World| open_scene(world, path));
+        }
+    }
+}
+
+/// Build a [`DynamicScene`] of the edited world.
+///
+/// The mesh, material and scene handles are replaced with [`SceneAssetPaths`]. The entities
+/// spawned by a [`SceneRoot`] are left out, they are spawned again from the scene asset.
+/// Other components that can't be serialized are left out with a warning.
+pub fn edited_scene(world: &mut World) -> DynamicScene {
+    let mut scene = extract_edited_world(world);
+
+    let instances: Vec<InstanceId> = world
+        .query_filtered::<&SceneInstance, With<SceneRoot>>()
+        .iter(world)
+        .map(|instance| **instance)
+        .collect();
+    let mut instance_entities = EntityHashSet::default();
+    if let Some(scene_spawner) = world.get_resource::<SceneSpawner>() {
+        for instance in instances {
+            instance_entities.extend(scene_spawner.iter_instance_entities(instance));
+        }
+    }
+    scene
+        .entities
+        .retain(|entity| !instance_entities.contains(&entity.entity));
+
+    let saved_as_paths = [
+        TypeId::of::<Mesh3d>(),
+        TypeId::of::<MeshMaterial3d<StandardMaterial>>(),
+        TypeId::of::<SceneRoot>(),
+    ];
+    let mut skipped = BTreeSet::new();
+    for entity in &mut scene.entities {
+        remove_children(entity, &instance_entities);
+        entity.components.retain(|component| {
+            !component
+                .get_represented_type_info()
+                .is_some_and(|info| saved_as_paths.contains(&info.type_id()))
+        });
+        let paths = scene_asset_paths(world, entity.entity, &mut skipped);
+        if !paths.is_empty() {
+            entity.components.push(Box::new(paths));
+        }
+    }
+
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    for entity in &mut scene.entities {
+        entity.components.retain(|component| {
+            let serializer = TypedReflectSerializer::new(component.as_ref(), &type_registry);
+            let serializable = ron::to_string(&serializer).is_ok();
+            if !serializable {
+                skipped.insert(
+                    component
+                        .get_represented_type_info()
+                        .map_or("unknown type", TypeInfo::type_path),
+                );
+            }
+            serializable
+        });
+    }
+    if !skipped.is_empty() {
+        warn!(
+            "Some components can't be saved to a scene and were left out: {}",
+            skipped.into_iter().collect::<Vec<_>>().join(", ")
+        );
+    }
+
+    scene
+}
+
+/// The paths of the assets of `entity`.
+///
+/// The components holding assets that weren't loaded from a file, and have no path, are added to `skipped`.
+fn scene_asset_paths(
+    world: &World,
+    entity: Entity,
+    skipped: &mut BTreeSet<&'static str>,
+) -> SceneAssetPaths {
+    SceneAssetPaths {
+        mesh: asset_path(
+            world.get::<Mesh3d>(entity).map(|mesh| &mesh.0),
+            Mesh3d::type_path(),
+            skipped,
+        ),
+        material: asset_path(
+            world
+                .get::<MeshMaterial3d<StandardMaterial>>(entity)
+                .map(|material| &material.0),
+            MeshMaterial3d::<StandardMaterial>::type_path(),
+            skipped,
+        ),
+        scene: asset_path(
+            world.get::<SceneRoot>(entity).map(|scene| &scene.0),
+            SceneRoot::type_path(),
+            skipped,
+        ),
+    }
+}
+
+fn asset_path<A: Asset>(
+    handle: Option<&Handle<A>>,
+    type_path: &'static str,
+    skipped: &mut BTreeSet<&'static str>,
+) -> Option<String> {
+    let path = handle?.path().map(ToString::to_string);
+    if path.is_none() {
+        skipped.insert(type_path);
+    }
+    path
+}
+
+/// Remove the `removed` entities from the [`Children`] of `entity`.
+fn remove_children(entity: &mut DynamicEntity, removed: &EntityHashSet) {
+    let Some(index) = entity
+        .components
+        .iter()
+        .position(|component| component.represents::<Children>())
+    else {
+        return;
+    };
+    let ReflectMut::TupleStruct(children) = entity.components[index].reflect_mut() else {
+        return;
+    };
+    let Some(ReflectMut::List(children)) = children.field_mut(0).map(PartialReflect::reflect_mut)
+    else {
+        return;
+    };
+    let mut i = 0;
+    while i < children.len() {
+        let removed_child = children
+            .get(i)
+            .and_then(<dyn PartialReflect>::try_downcast_ref::<Entity>)
+            .is_some_and(|child| removed.contains(child));
+        if removed_child {
+            children.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    if children.is_empty() {
+        entity.components.remove(index);
+    }
+}
+
+/// Clone the reflected components of the edited world into a [`DynamicScene`].
+pub(crate) fn extract_edited_world(world: &mut World) -> DynamicScene {
+    let entities: Vec<Entity> = world
+        .query_filtered::<Entity, EditedEntityFilter>()
+        .iter(world)
+        .collect();
+    DynamicSceneBuilder::from_world(world)
+        // Computed from the other components when the scene is spawned
+        .deny_component::<GlobalTransform>()
+        .deny_component::<InheritedVisibility>()
+        .deny_component::<ViewVisibility>()
+        .extract_entities(entities.into_iter())
+        .build()
+}
+
+/// Save the edited world to the scene file at `path`, and make it the [`EditedScenePath`] once written.
+pub fn save_scene(world: &mut World, path: PathBuf) {
+    let scene = edited_scene(world);
+    let serialized = {
+        let type_registry = world.resource::<AppTypeRegistry>().read();
+        match scene.serialize(&type_registry) {
+            Ok(serialized) => serialized,
+            Err(error) => {
+                error!("Failed to serialize the scene: {error}");
+                return;
+            }
+        }
+    };
+
+    let task_path = path.clone();
+    let task = IoTaskPool::get().spawn(async move {
+        File::create(&task_path).and_then(|mut file| file.write_all(serialized.as_bytes()))
+    });
+    world.resource_mut::<SceneSaveTasks>().0.push((path, task));
+}
+
+fn poll_scene_saves(
+    mut save_tasks: ResMut<SceneSaveTasks>,
+    mut scene_path: ResMut<EditedScenePath>,
+) {
+    save_tasks.0.retain_mut(|(path, task)| {
+        let Some(result) = block_on(future::poll_once(task)) else {
+            return true;
+        };
+        match result {
+            Ok(()) => {
+                info!("Saved the scene to {}", path.display());
+                scene_path.0 = Some(path.clone());
+            }
+            Err(error) => error!("Failed to write the scene to {}: {error}", path.display()),
+        }
+        false
+    });
+}
+
+/// Replace the edited world with the scene file at `path`, and make it the [`EditedScenePath`].
+///
+/// The edited world is only replaced once the scene file is read, it is left as it is if the
+/// scene file can't be read. The file is read from disk rather than loaded as an asset, so a
+/// scene that was just saved isn't opened from the asset cache.
+pub fn open_scene(world: &mut World, path: PathBuf) {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let task_path = path.clone();
+    let task = IoTaskPool::get().spawn(async move {
+        let serialized = std::fs::read_to_string(&task_path).map_err(ToString::to_string)?;
+        deserialize_scene(&serialized, &type_registry.read())
+    });
+    world.resource_mut::<OpeningScene>().0 = Some((path, task));
+}
+
+/// Deserialize the content of a scene file.
+fn deserialize_scene(
+    serialized: &str,
+    type_registry: &TypeRegistry,
+) -> Result<DynamicScene, String> {
+    let mut deserializer =
+        ron::de::Deserializer::from_str(serialized).map_err(ToString::to_string)?;
+    SceneDeserializer { type_registry }
+        .deserialize(&mut deserializer)
+        .map_err(ToString::to_string)
+}
+
+/// Open the scene files double-clicked in the asset browser.
+fn open_double_clicked_scene(
+    mut commands: Commands,
+    mut double_clicked_events: EventReader<AssetDoubleClicked>,
+    default_source_path: Option<Res<DefaultSourceFilePath>>,
+) {
+    for event in double_clicked_events.read() {
+        let is_scene = event.path.get_full_extension().is_some_and(|extension| {
+            matches!(extension.to_lowercase().as_str(), "scn" | "scn.ron")
+        });
+        if !is_scene {
+            continue;
+        }
+        // The scene is saved back to the same file, which needs its path on disk
+        let (AssetSourceId::Default, Some(default_source_path)) =
+            (event.path.source(), &default_source_path)
+        else {
+            warn!(
+                "Can't open the scene {}, only the scenes of the default asset source can be edited",
+                event.path
+            );
+            continue;
+        };
+        let path = default_source_path.0.join(event.path.path());
+        commands.queue(move |world: &mut World| open_scene(world, path));
+    }
+}
+
+fn spawn_opened_scene(mut commands: Commands, mut opening: ResMut<OpeningScene>) {
+    let Some((_, task)) = &mut opening.0 else {
+        return;
+    };
+    let Some(result) = block_on(future::poll_once(task)) else {
+        return;
+    };
+    let Some((path, _)) = opening.0.take() else {
+        return;
+    };
+    let scene = match result {
+        Ok(scene) => scene,
+        Err(error) => {
+            error!("Failed to open the scene {}: {error}", path.display());
+            return;
+        }
+    };
+
+    commands.queue(move |world: &mut World| {
+        let roots: Vec<Entity> = world
+            .query_filtered::<Entity, (EditedEntityFilter, Without<Parent>)>()
+            .iter(world)
+            .collect();
+        for root in roots {
+            world.entity_mut(root).despawn_recursive();
+        }
+        world.resource_mut::<EditorSelection>().clear();
+
+        // Spawn the entities at the root of the world rather than under a `DynamicSceneRoot`, so
+        // the scene is saved back the same way
+        if let Err(error) = scene.write_to_world(world, &mut EntityHashMap::default()) {
+            error!("Failed to open the scene {}: {error}", path.display());
+            return;
+        }
+        world.resource_mut::<EditedScenePath>().0 = Some(path);
+    });
+}
+
+/// Load the assets of a spawned [`SceneAssetPaths`] into their components.
+fn load_scene_asset_paths(
+    trigger: Trigger<OnAdd, SceneAssetPaths>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    paths_query: Query<&SceneAssetPaths>,
+) {
+    let entity = trigger.entity();
+    let Ok(paths) = paths_query.get(entity) else {
+        return;
+    };
+    let mut entity = commands.entity(entity);
+    if let Some(path) = &paths.mesh {
+        entity.insert(Mesh3d(asset_server.load(path.clone())));
+    }
+    if let Some(path) = &paths.material {
+        entity.insert(MeshMaterial3d::<StandardMaterial>(
+            asset_server.load(path.clone()),
+        ));
+    }
+    if let Some(path) = &paths.scene {
+        entity.insert(SceneRoot(asset_server.load(path.clone())));
+    }
+    entity.remove::<SceneAssetPaths>();
+}
+
+/// Make sure `path` ends with `.scn.ron`, so the asset server picks the scene loader for it.
+fn with_scene_extension(mut path: PathBuf) -> PathBuf {
+    if !path.to_string_lossy().ends_with(".scn.ron") {
+        path.set_extension("scn.ron");
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_editor_core::EditorEntity;
+
+    use super::*;
+
+    fn scene_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Mesh>()
+            .init_asset::<StandardMaterial>()
+            .register_type::<Name>()
+            .register_type::<Transform>()
+            .register_type::<Mesh3d>()
+            .register_type::<SceneAssetPaths>()
+            .add_observer(load_scene_asset_paths);
+        app
+    }
+
+    #[test]
+    fn test_save_and_reopen() {
+        let mut app = scene_app();
+        let mesh_path = "models/cube.glb#Mesh0/Primitive0";
+        let mesh = app
+            .world()
+            .resource::<AssetServer>()
+            .load::<Mesh>(mesh_path);
+        let generated_mesh = app
+            .world_mut()
+            .resource_mut::<Assets<Mesh>>()
+            .add(Cuboid::default());
+        app.world_mut().spawn((
+            Name::new("Cube"),
+            Transform::from_xyz(1.0, 2.0, 3.0),
+            Mesh3d(mesh),
+        ));
+        app.world_mut().spawn((
+            Name::new("Generated"),
+            Transform::default(),
+            Mesh3d(generated_mesh),
+        ));
+        app.world_mut()
+            .spawn((Name::new("Editor"), Transform::default(), EditorEntity));
+
+        let serialized = {
+            let scene = edited_scene(app.world_mut());
+            let type_registry = app.world().resource::<AppTypeRegistry>().read();
+            scene.serialize(&type_registry).unwrap()
+        };
+
+        let mut reopened = scene_app();
+        let scene = {
+            let type_registry = reopened.world().resource::<AppTypeRegistry>().read();
+            deserialize_scene(&serialized, &type_registry).unwrap()
+        };
+        scene
+            .write_to_world(reopened.world_mut(), &mut EntityHashMap::default())
+            .unwrap();
+        reopened.world_mut().flush();
+
+        let mut query =
+            reopened
+                .world_mut()
+                .query::<(&Name, &Transform, Option<&Mesh3d>, Has<SceneAssetPaths>)>();
+        let mut entities: Vec<_> = query
+            .iter(reopened.world())
+            .map(|(name, transform, mesh, has_paths)| {
+                (
+                    name.as_str().to_string(),
+                    *transform,
+                    mesh.and_then(|mesh| mesh.0.path()).map(ToString::to_string),
+                    has_paths,
+                )
+            })
+            .collect();
+        entities.sort_by(|(a, ..), (b, ..)| a.cmp(b));
+        assert_eq!(
+            entities,
+            vec![
+                (
+                    "Cube".to_string(),
+                    Transform::from_xyz(1.0, 2.0, 3.0),
+                    Some(mesh_path.to_string()),
+                    false
+                ),
+                // Generated assets can't be saved
+                ("Generated".to_string(), Transform::default(), None, false),
+            ]
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 
+use bevy_editor_core::EditorEntity;
 use bevy_editor_styles::Theme;
 use bevy_footer_bar::{FooterBarNode, FooterBarPlugin, FooterBarSet};
 use bevy_menu_bar::{MenuBarNode, MenuBarPlugin, MenuBarSet};
@@ -33,6 +34,7 @@ fn ui_setup(mut commands: Commands, theme: Res<Theme>) {
             order: 10,
             ..default()
         },
+        EditorEntity,
     ));
 
     commands
@@ -13,6 +13,7 @@ use bevy::window::PrimaryWindow;
 use bevy::{app::prelude::*, picking::pointer::PointerInput};
 use bevy::{ecs::prelude::*, picking::pointer::PointerAction};
 use bevy_derive::{Deref, DerefMut};
+use bevy_editor_core::PointerCapture;
 
 use bevy::picking::pointer::{PointerId, PointerInteraction, PointerLocation, PointerMap};
 
@@ -66,12 +67,16 @@ impl Plugin for DefaultInputPlugin {
 }
 
 /// A default implementation of an input system
+///
+/// No motion is started while the [`PointerCapture`] is captured by another tool.
+#[allow(clippy::too_many_arguments)]
 pub fn default_camera_inputs(
     pointers: Query<(&PointerId, &PointerLocation)>,
     pointer_map: Res<CameraPointerMap>,
     mut controller: EventWriter<EditorCamInputEvent>,
     mut mouse_wheel: EventReader<MouseWheel>,
     mouse_input: Res<ButtonInput<MouseButton>>,
+    pointer_capture: Option<Res<PointerCapture>>,
     cameras: Query<(Entity, &Camera, &EditorCam)>,
     primary_window: Query<Entity, With<PrimaryWindow>>,
 ) {
@@ -99,12 +104,18 @@ pub fn default_camera_inputs(
         }
     }
 
+    let captured = pointer_capture
+        .as_deref()
+        .is_some_and(PointerCapture::is_captured);
     for (&pointer, pointer_location) in pointers
         .iter()
         .filter_map(|(id, loc)| loc.location().map(|loc| (id, loc)))
     {
         match pointer {
             PointerId::Mouse => {
+                if captured {
+                    continue; // Another tool is using the press.
+                }
                 let Some((camera, ..)) = cameras.iter().find(|(_, camera, _)| {
                     pointer_location.is_in_viewport(camera, &primary_window)
                 }) else {
@@ -0,0 +1,226 @@
+//! Editor state shared between the different panes of the Bevy Editor.
+//!
+//! Panes don't depend on each other, so anything that more than one of them needs to agree on,
+//! like which entities are selected, lives here.
+
+use std::time::Duration;
+
+use bevy::{ecs::entity::Entities, prelude::*};
+
+pub mod raycast;
+
+/// The Bevy Editor Core Plugin.
+pub struct EditorCorePlugin;
+
+impl Plugin for EditorCorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditorSelection>()
+            .init_resource::<TextInputFocus>()
+            .init_resource::<PointerCapture>()
+            .add_systems(Last, remove_despawned_from_selection);
+    }
+}
+
+/// Maximum delay between two clicks on the same item for them to count as a double-click.
+pub const DOUBLE_CLICK_DELAY: Duration = Duration::from_millis(500);
+
+/// Marker for the entities that belong to the editor itself, like viewport cameras and grids,
+/// rather than to the edited world.
+#[derive(Component, Default, Debug, Clone, Copy)]
+pub struct EditorEntity;
+
+/// Query filter matching the entities of the edited world, leaving out the editor UI and the
+/// [`EditorEntity`]s.
+pub type EditedEntityFilter = (With<Transform>, Without<Node>, Without<EditorEntity>);
+
+/// Marker for entities that can't be selected from the viewports or edited.
+///
+/// Locked entities are still listed in the scene tree, where they can be unlocked.
+#[derive(Component, Default, Debug, Clone, Copy)]
+pub struct Locked;
+
+/// The entities currently selected in the editor.
+///
+/// The selection is ordered: the last selected entity is the primary one, which is the entity
+/// panes showing a single entity, like the inspector, should display.
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq)]
+pub struct EditorSelection {
+    entities: Vec<Entity>,
+}
+
+impl EditorSelection {
+    /// Iterate over the selected entities, from the first to the last selected.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = Entity> + '_ {
+        self.entities.iter().copied()
+    }
+
+    /// The last selected entity.
+    pub fn primary(&self) -> Option<Entity> {
+        self.entities.last().copied()
+    }
+
+    /// Whether `entity` is selected.
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entities.contains(&entity)
+    }
+
+    /// The number of selected entities.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Whether nothing is selected.
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Replace the selection with `entity` alone.
+    pub fn set(&mut self, entity: Entity) {
+        self.entities.clear();
+        self.entities.push(entity);
+    }
+
+    /// Add `entity` to the selection and make it the primary one.
+    pub fn add(&mut self, entity: Entity) {
+        self.entities.retain(|e| *e != entity);
+        self.entities.push(entity);
+    }
+
+    /// Remove `entity` from the selection.
+    pub fn remove(&mut self, entity: Entity) {
+        self.entities.retain(|e| *e != entity);
+    }
+
+    /// Remove `entity` from the selection if it is selected, add it otherwise.
+    pub fn toggle(&mut self, entity: Entity) {
+        if self.contains(entity) {
+            self.remove(entity);
+        } else {
+            self.add(entity);
+        }
+    }
+
+    /// Deselect everything.
+    pub fn clear(&mut self) {
+        self.entities.clear();
+    }
+}
+
+impl Extend<Entity> for EditorSelection {
+    fn extend<T: IntoIterator<Item = Entity>>(&mut self, iter: T) {
+        for entity in iter {
+            self.add(entity);
+        }
+    }
+}
+
+/// The text input that receives the keyboard, if any.
+///
+/// Keyboard shortcuts must be ignored while a text input is focused, the keys are typed in it.
+/// The input is identified by an entity it owns, like its node or the entity it edits. Focusing an
+/// input takes the focus away from the previous one, which must then stop reading the keyboard.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextInputFocus(pub Option<Entity>);
+
+impl TextInputFocus {
+    /// Whether a text input is focused.
+    pub fn is_focused(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Whether the text input identified by `owner` is focused.
+    pub fn is_focused_by(&self, owner: Entity) -> bool {
+        self.0 == Some(owner)
+    }
+
+    /// Remove the focus from the text input identified by `owner`, if it has it.
+    pub fn release(&mut self, owner: Entity) {
+        if self.is_focused_by(owner) {
+            self.0 = None;
+        }
+    }
+}
+
+/// Whether the current mouse press has been captured by an editor tool.
+///
+/// A tool starting an interaction on a press, like grabbing a gizmo handle, captures the pointer
+/// so that the other tools, the camera controls included, ignore the press. It releases the
+/// pointer once the interaction ends.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointerCapture {
+    captured: bool,
+}
+
+impl PointerCapture {
+    /// Whether a tool has captured the pointer.
+    pub fn is_captured(&self) -> bool {
+        self.captured
+    }
+
+    /// Capture the pointer until [`release`](Self::release) is called.
+    pub fn capture(&mut self) {
+        self.captured = true;
+    }
+
+    /// Release the pointer captured by [`capture`](Self::capture).
+    pub fn release(&mut self) {
+        self.captured = false;
+    }
+}
+
+fn remove_despawned_from_selection(mut selection: ResMut<EditorSelection>, entities: &Entities) {
+    // Only take a mutable borrow when needed, to not trigger change detection every frame
+    if selection.iter().any(|entity| !entities.contains(entity)) {
+        selection
+            .entities
+            .retain(|entity| entities.contains(*entity));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_order() {
+        let mut world = World::new();
+        let [a, b, c] = [(); 3].map(|_| world.spawn_empty().id());
+
+        let mut selection = EditorSelection::default();
+        selection.set(a);
+        selection.extend([b, c]);
+        assert_eq!(selection.iter().collect::<Vec<_>>(), vec![a, b, c]);
+        assert_eq!(selection.primary(), Some(c));
+
+        // Adding an already selected entity makes it the primary one
+        selection.add(a);
+        assert_eq!(selection.iter().collect::<Vec<_>>(), vec![b, c, a]);
+
+        selection.toggle(c);
+        assert!(!selection.contains(c));
+        selection.toggle(c);
+        assert_eq!(selection.primary(), Some(c));
+
+        selection.set(b);
+        assert_eq!(selection.len(), 1);
+        assert_eq!(selection.primary(), Some(b));
+    }
+
+    #[test]
+    fn test_despawned_entities_are_deselected() {
+        let mut app = App::new();
+        app.add_plugins(EditorCorePlugin);
+
+        let a = app.world_mut().spawn_empty().id();
+        let b = app.world_mut().spawn_empty().id();
+        app.world_mut()
+            .resource_mut::<EditorSelection>()
+            .extend([a, b]);
+
+        app.world_mut().despawn(a);
+        app.update();
+
+        let selection = app.world().resource::<EditorSelection>();
+        assert_eq!(selection.iter().collect::<Vec<_>>(), vec![b]);
+    }
+}
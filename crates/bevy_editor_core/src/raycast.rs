@@ -0,0 +1,146 @@
+//! Ray casting helpers to find what is under the pointer in a viewport.
+//!
+//! These are shared by everything reacting to the pointer in the viewports, like the selection,
+//! the transform gizmos and dropping assets.
+
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::{PrimitiveTopology, VertexAttributeValues},
+        primitives::Aabb,
+    },
+};
+
+/// Build the world space ray going through `viewport_position` (in logical pixels).
+pub fn pointer_ray(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    viewport_position: Vec2,
+) -> Option<Ray3d> {
+    let target_size = camera.logical_viewport_size()?;
+    let mut ndc = viewport_position * 2. / target_size - Vec2::ONE;
+    // Flip the Y co-ordinate origin from the top to the bottom.
+    ndc.y = -ndc.y;
+    let near = camera.ndc_to_world(camera_transform, ndc.extend(1.))?;
+    // Using EPSILON because an ndc with Z = 0 returns NaNs.
+    let far = camera.ndc_to_world(camera_transform, ndc.extend(f32::EPSILON))?;
+    Some(Ray3d {
+        origin: near,
+        direction: Dir3::new(far - near).ok()?,
+    })
+}
+
+/// The position of `world_position` in the viewport (in logical pixels), if it is in front of the camera.
+pub fn viewport_position(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    world_position: Vec3,
+) -> Option<Vec2> {
+    let target_size = camera.logical_viewport_size()?;
+    let ndc = camera.world_to_ndc(camera_transform, world_position)?;
+    if !(0.0..=1.0).contains(&ndc.z) {
+        return None;
+    }
+    let mut position = (ndc.truncate() + Vec2::ONE) / 2.0;
+    // Flip the Y co-ordinate origin from the bottom to the top.
+    position.y = 1.0 - position.y;
+    Some(position * target_size)
+}
+
+/// World space distance along `ray` to the oriented bounding box of a mesh, if it is hit.
+pub fn ray_aabb_distance(ray: Ray3d, aabb: &Aabb, transform: &GlobalTransform) -> Option<f32> {
+    let world_from_local = transform.affine();
+    let local_from_world = world_from_local.inverse();
+    let origin = local_from_world.transform_point3(ray.origin);
+    let direction = local_from_world.transform_vector3(*ray.direction);
+
+    // Slab test in the local space of the mesh
+    let t1 = (Vec3::from(aabb.min()) - origin) / direction;
+    let t2 = (Vec3::from(aabb.max()) - origin) / direction;
+    let t_near = t1.min(t2).max_element().max(0.);
+    let t_far = t1.max(t2).min_element();
+    if t_near > t_far {
+        return None;
+    }
+
+    // `t_near` is measured in local units, convert the hit back to a world space distance
+    let hit = world_from_local.transform_point3(origin + direction * t_near);
+    Some(ray.origin.distance(hit))
+}
+
+/// World space distance along `ray` to the closest triangle of `mesh`, if it is hit.
+///
+/// Returns `None` if the mesh isn't made of triangles, as there is nothing to test against.
+/// Use the bounding box of the mesh instead in that case.
+pub fn ray_mesh_distance(
+    ray: Ray3d,
+    mesh: &Mesh,
+    transform: &GlobalTransform,
+) -> Option<Option<f32>> {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        return None;
+    }
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return None;
+    };
+
+    let world_from_local = transform.affine();
+    let local_from_world = world_from_local.inverse();
+    let origin = local_from_world.transform_point3(ray.origin);
+    let direction = local_from_world.transform_vector3(*ray.direction);
+
+    let triangle_distance = |triangle: &[usize]| {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]]
+            .map(|index| positions.get(index).copied().map(Vec3::from));
+        ray_triangle_distance(origin, direction, [a?, b?, c?])
+    };
+    let t = match mesh.indices() {
+        Some(indices) => indices
+            .iter()
+            .collect::<Vec<_>>()
+            .chunks_exact(3)
+            .filter_map(triangle_distance)
+            .min_by(f32::total_cmp),
+        None => (0..positions.len())
+            .collect::<Vec<_>>()
+            .chunks_exact(3)
+            .filter_map(triangle_distance)
+            .min_by(f32::total_cmp),
+    };
+
+    // `t` is measured in local units, convert the hit back to a world space distance
+    Some(t.map(|t| {
+        let hit = world_from_local.transform_point3(origin + direction * t);
+        ray.origin.distance(hit)
+    }))
+}
+
+/// Distance along `direction` from `origin` to the triangle, in units of `direction`.
+///
+/// Uses the Möller–Trumbore algorithm, both faces of the triangle are hit.
+fn ray_triangle_distance(origin: Vec3, direction: Vec3, [a, b, c]: [Vec3; 3]) -> Option<f32> {
+    let edge_1 = b - a;
+    let edge_2 = c - a;
+    let p = direction.cross(edge_2);
+    let determinant = edge_1.dot(p);
+    if determinant.abs() < f32::EPSILON {
+        // The ray is parallel to the triangle
+        return None;
+    }
+    let inverse_determinant = 1.0 / determinant;
+
+    let s = origin - a;
+    let u = s.dot(p) * inverse_determinant;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = s.cross(edge_1);
+    let v = direction.dot(q) * inverse_determinant;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = edge_2.dot(q) * inverse_determinant;
+    (t >= 0.0).then_some(t)
+}
@@ -26,17 +26,6 @@ pub struct PaneLayoutPlugin;
 
 impl Plugin for PaneLayoutPlugin {
     fn build(&self, app: &mut App) {
-        let mut pane_registry = app.world_mut().get_resource_or_init::<PaneRegistry>();
-
-        // TODO Move these registrations to their respective crates.
-        pane_registry.register("Properties", |mut _commands, _pane_root| {
-            // Todo
-        });
-
-        pane_registry.register("Scene Tree", |mut _commands, _pane_root| {
-            // Todo
-        });
-
         app.init_resource::<DragState>()
             .init_resource::<PaneRegistry>()
             .add_systems(Startup, setup.in_set(PaneLayoutSet))
@@ -173,7 +162,27 @@ fn setup(
         .set_parent(divider)
         .id();
 
-    spawn_pane(&mut commands, &theme, 0.70, "Viewport 3D").set_parent(asset_browser_divider);
+    // Perspective and orthographic views of the scene
+    let viewports_divider = spawn_divider(&mut commands, Divider::Horizontal, 0.70)
+        .set_parent(asset_browser_divider)
+        .id();
+
+    let left_viewports_divider = spawn_divider(&mut commands, Divider::Vertical, 0.5)
+        .set_parent(viewports_divider)
+        .id();
+    spawn_pane(&mut commands, &theme, 0.5, "Viewport 3D").set_parent(left_viewports_divider);
+    spawn_resize_handle(&mut commands, Divider::Vertical).set_parent(left_viewports_divider);
+    spawn_pane(&mut commands, &theme, 0.5, "Viewport 3D Front").set_parent(left_viewports_divider);
+
+    spawn_resize_handle(&mut commands, Divider::Horizontal).set_parent(viewports_divider);
+
+    let right_viewports_divider = spawn_divider(&mut commands, Divider::Vertical, 0.5)
+        .set_parent(viewports_divider)
+        .id();
+    spawn_pane(&mut commands, &theme, 0.5, "Viewport 3D Top").set_parent(right_viewports_divider);
+    spawn_resize_handle(&mut commands, Divider::Vertical).set_parent(right_viewports_divider);
+    spawn_pane(&mut commands, &theme, 0.5, "Viewport 3D Side").set_parent(right_viewports_divider);
+
     spawn_resize_handle(&mut commands, Divider::Vertical).set_parent(asset_browser_divider);
     spawn_pane(&mut commands, &theme, 0.30, "Asset Browser").set_parent(asset_browser_divider);
 }
@@ -0,0 +1,123 @@
+//! Drawing of the gizmo handles.
+
+use bevy::{prelude::*, render::view::RenderLayers};
+use bevy_editor_core::{EditorSelection, Locked};
+use bevy_editor_styles::Theme;
+
+use crate::{
+    frame::{
+        GizmoFrame, Handle, CENTER_RADIUS, PLANE_HANDLE_MAX, PLANE_HANDLE_MIN, RING_RADIUS,
+        SCALE_HANDLE_SIZE,
+    },
+    gizmo_targets, gizmo_view_layer, GizmoInteraction, GizmoMode, TransformGizmoGroup,
+    TransformGizmoSettings,
+};
+
+/// Color of the hovered or dragged handle.
+const ACTIVE_COLOR: Color = Color::srgb(1.0, 0.85, 0.2);
+
+/// Draw the gizmo of the view `VIEW`, sized for the camera rendering its [`gizmo_view_layer`].
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn draw_gizmo<const VIEW: usize>(
+    mut gizmos: Gizmos<TransformGizmoGroup<VIEW>>,
+    theme: Res<Theme>,
+    settings: Res<TransformGizmoSettings>,
+    selection: Res<EditorSelection>,
+    interaction: Res<GizmoInteraction>,
+    cameras: Query<(&GlobalTransform, &Projection, &RenderLayers), With<Camera>>,
+    global_transforms: Query<&GlobalTransform>,
+    movable: Query<(), (With<Transform>, Without<Locked>)>,
+    parents: Query<&Parent>,
+) {
+    let Some(anchor) = gizmo_targets(&selection, &movable, &parents)
+        .last()
+        .and_then(|entity| global_transforms.get(*entity).ok())
+    else {
+        return;
+    };
+
+    let view_layer = RenderLayers::layer(gizmo_view_layer(VIEW));
+    let Some((camera_transform, projection, _)) = cameras
+        .iter()
+        .find(|(.., layers)| layers.intersects(&view_layer))
+    else {
+        return;
+    };
+
+    let (mode, active_handle) = match &interaction.drag {
+        Some(drag) => (drag.mode, Some(drag.handle)),
+        None => (settings.mode, interaction.hovered),
+    };
+    let frame = GizmoFrame::new(
+        &TransformGizmoSettings {
+            mode,
+            ..settings.clone()
+        },
+        anchor,
+        camera_transform,
+        projection,
+    );
+
+    let axis_colors = [
+        theme.viewport.x_axis_color,
+        theme.viewport.y_axis_color,
+        theme.viewport.z_axis_color,
+    ];
+    let color = |handle: Handle| {
+        if active_handle == Some(handle) {
+            return ACTIVE_COLOR;
+        }
+        match handle {
+            Handle::Axis(axis) | Handle::Plane(axis) => axis_colors[axis],
+            Handle::Center => theme.text.text_color,
+        }
+    };
+
+    for &handle in Handle::all(mode) {
+        match (mode, handle) {
+            (GizmoMode::Translate, Handle::Axis(axis)) => {
+                gizmos.arrow(frame.origin, frame.axis_end(axis), color(handle));
+            }
+            (GizmoMode::Rotate, Handle::Axis(axis)) => {
+                gizmos.circle(
+                    Isometry3d::new(
+                        frame.origin,
+                        Quat::from_rotation_arc(Vec3::Z, frame.axes[axis]),
+                    ),
+                    RING_RADIUS * frame.scale,
+                    color(handle),
+                );
+            }
+            (GizmoMode::Scale, Handle::Axis(axis)) => {
+                let end = frame.axis_end(axis);
+                gizmos.line(frame.origin, end, color(handle));
+                gizmos.cuboid(
+                    Transform::from_translation(end)
+                        .with_rotation(frame.rotation)
+                        .with_scale(Vec3::splat(SCALE_HANDLE_SIZE * frame.scale)),
+                    color(handle),
+                );
+            }
+            (_, Handle::Plane(axis)) => {
+                gizmos.linestrip(
+                    [
+                        (PLANE_HANDLE_MIN, PLANE_HANDLE_MIN),
+                        (PLANE_HANDLE_MAX, PLANE_HANDLE_MIN),
+                        (PLANE_HANDLE_MAX, PLANE_HANDLE_MAX),
+                        (PLANE_HANDLE_MIN, PLANE_HANDLE_MAX),
+                        (PLANE_HANDLE_MIN, PLANE_HANDLE_MIN),
+                    ]
+                    .map(|(u, v)| frame.plane_point(axis, u, v)),
+                    color(handle),
+                );
+            }
+            (_, Handle::Center) => {
+                gizmos.sphere(
+                    Isometry3d::new(frame.origin, frame.rotation),
+                    CENTER_RADIUS * frame.scale,
+                    color(handle),
+                );
+            }
+        }
+    }
+}
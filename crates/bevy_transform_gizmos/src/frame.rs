@@ -0,0 +1,257 @@
+//! Geometry of the gizmo handles, shared by the drawing and the picking of the gizmo.
+
+use bevy::prelude::*;
+
+use crate::{GizmoMode, GizmoSpace, TransformGizmoSettings};
+
+/// Length of the axis handles, in gizmo units.
+pub(crate) const AXIS_LENGTH: f32 = 1.0;
+/// Offset of the inner corner of the plane handles, in gizmo units.
+pub(crate) const PLANE_HANDLE_MIN: f32 = 0.25;
+/// Offset of the outer corner of the plane handles, in gizmo units.
+pub(crate) const PLANE_HANDLE_MAX: f32 = 0.45;
+/// Radius of the rotation rings, in gizmo units.
+pub(crate) const RING_RADIUS: f32 = 0.85;
+/// Size of the cubes at the end of the scale handles, in gizmo units.
+pub(crate) const SCALE_HANDLE_SIZE: f32 = 0.1;
+/// Radius of the uniform scale handle, in gizmo units.
+pub(crate) const CENTER_RADIUS: f32 = 0.1;
+/// How far from a handle the pointer can be to grab it, in gizmo units.
+const PICK_DISTANCE: f32 = 0.06;
+
+/// A part of the gizmo that can be dragged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Handle {
+    /// Along an axis for the translate and scale modes, around it for the rotate mode.
+    Axis(usize),
+    /// The plane orthogonal to an axis.
+    Plane(usize),
+    /// The center of the gizmo, for uniform scaling.
+    Center,
+}
+
+impl Handle {
+    /// The handles shown in `mode`.
+    pub(crate) fn all(mode: GizmoMode) -> &'static [Handle] {
+        match mode {
+            GizmoMode::Translate => &[
+                Handle::Axis(0),
+                Handle::Axis(1),
+                Handle::Axis(2),
+                Handle::Plane(0),
+                Handle::Plane(1),
+                Handle::Plane(2),
+            ],
+            GizmoMode::Rotate => &[Handle::Axis(0), Handle::Axis(1), Handle::Axis(2)],
+            GizmoMode::Scale => &[
+                Handle::Axis(0),
+                Handle::Axis(1),
+                Handle::Axis(2),
+                Handle::Center,
+            ],
+        }
+    }
+}
+
+/// Position, orientation and size of the gizmo as seen from a camera.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct GizmoFrame {
+    pub(crate) origin: Vec3,
+    pub(crate) rotation: Quat,
+    pub(crate) axes: [Vec3; 3],
+    /// World size of one gizmo unit, so the gizmo keeps the same size on screen.
+    pub(crate) scale: f32,
+}
+
+impl GizmoFrame {
+    pub(crate) fn new(
+        settings: &TransformGizmoSettings,
+        target: &GlobalTransform,
+        camera_transform: &GlobalTransform,
+        projection: &Projection,
+    ) -> Self {
+        let (_, target_rotation, origin) = target.to_scale_rotation_translation();
+        let rotation = if settings.space == GizmoSpace::Local || settings.mode == GizmoMode::Scale {
+            target_rotation
+        } else {
+            Quat::IDENTITY
+        };
+        let view_height = match projection {
+            Projection::Perspective(perspective) => {
+                let depth = (origin - camera_transform.translation())
+                    .dot(*camera_transform.forward())
+                    .max(perspective.near);
+                2.0 * depth * (perspective.fov / 2.0).tan()
+            }
+            Projection::Orthographic(orthographic) => orthographic.area.height(),
+        };
+
+        Self {
+            origin,
+            rotation,
+            axes: [rotation * Vec3::X, rotation * Vec3::Y, rotation * Vec3::Z],
+            scale: view_height * settings.size,
+        }
+    }
+
+    /// The world position at the end of the handle of `axis`.
+    pub(crate) fn axis_end(&self, axis: usize) -> Vec3 {
+        self.origin + self.axes[axis] * AXIS_LENGTH * self.scale
+    }
+
+    /// The world position at the given gizmo units along the two axes spanning the plane orthogonal to `axis`.
+    pub(crate) fn plane_point(&self, axis: usize, u: f32, v: f32) -> Vec3 {
+        let (a, b) = plane_axes(axis);
+        self.origin + (self.axes[a] * u + self.axes[b] * v) * self.scale
+    }
+
+    /// The handle under `ray`, if any.
+    pub(crate) fn pick(&self, mode: GizmoMode, ray: Ray3d) -> Option<Handle> {
+        // The center handle overlaps the start of the axis handles, it takes priority over them
+        if Handle::all(mode).contains(&Handle::Center)
+            && self.hit_distance(mode, Handle::Center, ray).is_some()
+        {
+            return Some(Handle::Center);
+        }
+        Handle::all(mode)
+            .iter()
+            .filter_map(|handle| Some((*handle, self.hit_distance(mode, *handle, ray)?)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(handle, _)| handle)
+    }
+
+    /// Distance along `ray` to `handle`, if it hits it.
+    fn hit_distance(&self, mode: GizmoMode, handle: Handle, ray: Ray3d) -> Option<f32> {
+        let pick_distance = PICK_DISTANCE * self.scale;
+        match (mode, handle) {
+            (GizmoMode::Rotate, Handle::Axis(axis)) => {
+                let distance =
+                    ray.intersect_plane(self.origin, InfinitePlane3d::new(self.axes[axis]))?;
+                let radius = ray.get_point(distance).distance(self.origin);
+                ((radius - RING_RADIUS * self.scale).abs() < pick_distance).then_some(distance)
+            }
+            (_, Handle::Axis(axis)) => {
+                let (_, t) = closest_points(ray, self.origin, self.axes[axis])?;
+                let point = self.origin + self.axes[axis] * t.clamp(0.0, AXIS_LENGTH * self.scale);
+                let distance = (point - ray.origin).dot(*ray.direction);
+                (distance > 0.0 && ray.get_point(distance).distance(point) < pick_distance)
+                    .then_some(distance)
+            }
+            (_, Handle::Plane(axis)) => {
+                let distance =
+                    ray.intersect_plane(self.origin, InfinitePlane3d::new(self.axes[axis]))?;
+                let offset = (ray.get_point(distance) - self.origin) / self.scale;
+                let (a, b) = plane_axes(axis);
+                let range = PLANE_HANDLE_MIN..=PLANE_HANDLE_MAX;
+                (range.contains(&offset.dot(self.axes[a]))
+                    && range.contains(&offset.dot(self.axes[b])))
+                .then_some(distance)
+            }
+            (_, Handle::Center) => {
+                let distance = (self.origin - ray.origin).dot(*ray.direction);
+                (distance > 0.0
+                    && ray.get_point(distance).distance(self.origin) < CENTER_RADIUS * self.scale)
+                    .then_some(distance)
+            }
+        }
+    }
+
+    /// The point of `handle` under `ray`: on the axis of axis handles in the translate and scale
+    /// modes, on the plane of the handle otherwise.
+    ///
+    /// The uniform scale handle uses the plane facing `view_direction`.
+    pub(crate) fn drag_point(
+        &self,
+        mode: GizmoMode,
+        handle: Handle,
+        ray: Ray3d,
+        view_direction: Vec3,
+    ) -> Option<Vec3> {
+        let plane_normal = match (mode, handle) {
+            (GizmoMode::Translate | GizmoMode::Scale, Handle::Axis(axis)) => {
+                let (_, t) = closest_points(ray, self.origin, self.axes[axis])?;
+                return Some(self.origin + self.axes[axis] * t);
+            }
+            (_, Handle::Axis(axis) | Handle::Plane(axis)) => self.axes[axis],
+            (_, Handle::Center) => view_direction,
+        };
+        let distance = ray.intersect_plane(self.origin, InfinitePlane3d::new(plane_normal))?;
+        Some(ray.get_point(distance))
+    }
+}
+
+/// The two axes spanning the plane orthogonal to `axis`.
+pub(crate) fn plane_axes(axis: usize) -> (usize, usize) {
+    ((axis + 1) % 3, (axis + 2) % 3)
+}
+
+/// Parameters of the closest points between `ray` and the line going through `origin` along the
+/// unit vector `direction`, as `(distance along the ray, distance along the line)`.
+///
+/// Returns `None` when they are parallel.
+fn closest_points(ray: Ray3d, origin: Vec3, direction: Vec3) -> Option<(f32, f32)> {
+    let offset = ray.origin - origin;
+    let b = ray.direction.dot(direction);
+    let d = ray.direction.dot(offset);
+    let e = direction.dot(offset);
+    let denominator = 1.0 - b * b;
+    if denominator < 1e-4 {
+        return None;
+    }
+    Some(((b * e - d) / denominator, (e - b * d) / denominator))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame() -> GizmoFrame {
+        GizmoFrame {
+            origin: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            axes: [Vec3::X, Vec3::Y, Vec3::Z],
+            scale: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_pick_handles() {
+        let frame = frame();
+        // Looking down at the gizmo from above
+        let ray_at = |x: f32, z: f32| Ray3d {
+            origin: Vec3::new(x, 10.0, z),
+            direction: Dir3::NEG_Y,
+        };
+
+        assert_eq!(
+            frame.pick(GizmoMode::Translate, ray_at(0.8, 0.0)),
+            Some(Handle::Axis(0))
+        );
+        assert_eq!(
+            frame.pick(GizmoMode::Translate, ray_at(0.35, 0.35)),
+            Some(Handle::Plane(1))
+        );
+        assert_eq!(frame.pick(GizmoMode::Translate, ray_at(0.7, 0.7)), None);
+        assert_eq!(
+            frame.pick(GizmoMode::Rotate, ray_at(0.0, RING_RADIUS)),
+            Some(Handle::Axis(1))
+        );
+        assert_eq!(
+            frame.pick(GizmoMode::Scale, ray_at(0.0, 0.0)),
+            Some(Handle::Center)
+        );
+    }
+
+    #[test]
+    fn test_drag_point_stays_on_axis() {
+        let frame = frame();
+        let ray = Ray3d {
+            origin: Vec3::new(2.0, 1.0, 5.0),
+            direction: Dir3::NEG_Z,
+        };
+        let point = frame
+            .drag_point(GizmoMode::Translate, Handle::Axis(0), ray, Vec3::NEG_Z)
+            .unwrap();
+        assert!(point.abs_diff_eq(Vec3::new(2.0, 0.0, 0.0), 1e-5));
+    }
+}
@@ -0,0 +1,276 @@
+//! Grabbing and dragging the gizmo handles with the mouse.
+
+use bevy::{
+    picking::pointer::{PointerId, PointerLocation},
+    prelude::*,
+    render::view::RenderLayers,
+    window::PrimaryWindow,
+};
+use bevy_editor_core::{raycast::pointer_ray, EditorSelection, Locked, PointerCapture};
+
+use crate::{
+    frame::{plane_axes, GizmoFrame, Handle},
+    gizmo_targets, GizmoMode, TransformGizmoEdit, TransformGizmoSettings, GIZMO_RENDER_LAYER,
+};
+
+/// The smallest scale factor a drag can apply.
+const MIN_SCALE_FACTOR: f32 = 0.01;
+
+/// The state of the interactions with the transform gizmo.
+#[derive(Resource, Default)]
+pub struct GizmoInteraction {
+    pub(crate) hovered: Option<Handle>,
+    pub(crate) drag: Option<GizmoDrag>,
+}
+
+impl GizmoInteraction {
+    /// Whether the pointer is over a handle of the gizmo.
+    pub fn is_hovered(&self) -> bool {
+        self.hovered.is_some()
+    }
+
+    /// Whether a handle of the gizmo is being dragged.
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+}
+
+pub(crate) struct GizmoDrag {
+    pub(crate) camera: Entity,
+    pub(crate) handle: Handle,
+    pub(crate) mode: GizmoMode,
+    /// The gizmo at the start of the drag.
+    frame: GizmoFrame,
+    view_direction: Vec3,
+    start_point: Vec3,
+    targets: Vec<DragTarget>,
+}
+
+/// An entity moved by the gizmo, with its state at the start of the drag.
+struct DragTarget {
+    entity: Entity,
+    start: Transform,
+    parent: GlobalTransform,
+}
+
+impl GizmoDrag {
+    /// The transform of `target` for the handle dragged to `point`.
+    fn apply(
+        &self,
+        target: &DragTarget,
+        point: Vec3,
+        settings: &TransformGizmoSettings,
+        snapping: bool,
+    ) -> Transform {
+        let snap = |value: f32, increment: f32| {
+            if snapping && increment > 0.0 {
+                (value / increment).round() * increment
+            } else {
+                value
+            }
+        };
+        let frame = &self.frame;
+        let mut transform = target.start;
+
+        match self.mode {
+            GizmoMode::Translate => {
+                let offset = point - self.start_point;
+                let constrained_axes = match self.handle {
+                    Handle::Axis(axis) => vec![axis],
+                    Handle::Plane(axis) => {
+                        let (a, b) = plane_axes(axis);
+                        vec![a, b]
+                    }
+                    Handle::Center => Vec::new(),
+                };
+                let offset: Vec3 = constrained_axes
+                    .into_iter()
+                    .map(|axis| {
+                        let axis = frame.axes[axis];
+                        axis * snap(offset.dot(axis), settings.translate_snap)
+                    })
+                    .sum();
+                transform.translation += target.parent.affine().inverse().transform_vector3(offset);
+            }
+            GizmoMode::Rotate => {
+                let Handle::Axis(axis) = self.handle else {
+                    return transform;
+                };
+                let axis = frame.axes[axis];
+                let from = self.start_point - frame.origin;
+                let to = point - frame.origin;
+                let angle = axis.dot(from.cross(to)).atan2(from.dot(to));
+                let rotation = Quat::from_axis_angle(axis, snap(angle, settings.rotate_snap));
+                // Rotate in world space, around the origin of the entity
+                let (_, parent_rotation, _) = target.parent.to_scale_rotation_translation();
+                transform.rotation =
+                    parent_rotation.inverse() * rotation * parent_rotation * transform.rotation;
+            }
+            GizmoMode::Scale => {
+                let from = self.start_point - frame.origin;
+                let to = point - frame.origin;
+                let factor = match self.handle {
+                    Handle::Axis(axis) => to.dot(frame.axes[axis]) / from.dot(frame.axes[axis]),
+                    Handle::Plane(_) | Handle::Center => to.length() / from.length(),
+                };
+                if !factor.is_finite() {
+                    return transform;
+                }
+                // Snapping can round the factor down to zero, and scaling through zero flips the entity
+                let factor = snap(factor, settings.scale_snap).max(MIN_SCALE_FACTOR);
+                match self.handle {
+                    Handle::Axis(axis) => transform.scale[axis] *= factor,
+                    Handle::Plane(_) | Handle::Center => transform.scale *= factor,
+                }
+            }
+        }
+        transform
+    }
+}
+
+/// Hover, grab and drag the handles of the gizmo, and move the selected entities accordingly.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn drag_gizmo(
+    mut interaction: ResMut<GizmoInteraction>,
+    mut pointer_capture: ResMut<PointerCapture>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<TransformGizmoSettings>,
+    selection: Res<EditorSelection>,
+    mut edits: EventWriter<TransformGizmoEdit>,
+    pointers: Query<(&PointerId, &PointerLocation)>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    cameras: Query<(
+        Entity,
+        &Camera,
+        &GlobalTransform,
+        &Projection,
+        &RenderLayers,
+    )>,
+    global_transforms: Query<&GlobalTransform>,
+    mut transforms: Query<&mut Transform>,
+    movable: Query<(), (With<Transform>, Without<Locked>)>,
+    parents: Query<&Parent>,
+) {
+    // End the drag when the button is released, wherever the pointer is
+    if interaction.drag.is_some() && !mouse.pressed(MouseButton::Left) {
+        let drag = interaction.drag.take().unwrap();
+        pointer_capture.release();
+        for target in drag.targets {
+            let Ok(transform) = transforms.get(target.entity) else {
+                continue;
+            };
+            if *transform != target.start {
+                edits.send(TransformGizmoEdit {
+                    entity: target.entity,
+                    before: target.start,
+                    after: *transform,
+                });
+            }
+        }
+        return;
+    }
+
+    let Some(location) = pointers
+        .iter()
+        .find_map(|(id, location)| (*id == PointerId::Mouse).then(|| location.location())?)
+    else {
+        return;
+    };
+    let gizmo_layer = RenderLayers::layer(GIZMO_RENDER_LAYER);
+    let viewport_ray = |camera: &Camera, camera_transform: &GlobalTransform| {
+        if !location.is_in_viewport(camera, &primary_window) {
+            return None;
+        }
+        pointer_ray(camera, camera_transform, location.position)
+    };
+
+    if let Some(drag) = &interaction.drag {
+        let Ok((_, camera, camera_transform, ..)) = cameras.get(drag.camera) else {
+            return;
+        };
+        let Some(ray) = viewport_ray(camera, camera_transform) else {
+            return;
+        };
+        let Some(point) = drag
+            .frame
+            .drag_point(drag.mode, drag.handle, ray, drag.view_direction)
+        else {
+            return;
+        };
+        let ctrl = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+        for target in &drag.targets {
+            if let Ok(mut transform) = transforms.get_mut(target.entity) {
+                *transform = drag.apply(target, point, &settings, settings.snapping != ctrl);
+            }
+        }
+        return;
+    }
+
+    let hovered_camera = cameras
+        .iter()
+        .filter(|(.., layers)| layers.intersects(&gizmo_layer))
+        .find_map(|(entity, camera, camera_transform, projection, _)| {
+            Some((
+                entity,
+                camera_transform,
+                projection,
+                viewport_ray(camera, camera_transform)?,
+            ))
+        });
+    let Some((camera, camera_transform, projection, ray)) = hovered_camera else {
+        interaction.hovered = None;
+        return;
+    };
+
+    let drag_targets = gizmo_targets(&selection, &movable, &parents);
+    // The gizmo is placed on the primary entity of the selection
+    let Some(anchor) = drag_targets
+        .last()
+        .and_then(|entity| global_transforms.get(*entity).ok())
+    else {
+        interaction.hovered = None;
+        return;
+    };
+    let frame = GizmoFrame::new(&settings, anchor, camera_transform, projection);
+    interaction.hovered = frame.pick(settings.mode, ray);
+
+    let Some(handle) = interaction.hovered else {
+        return;
+    };
+    if !mouse.just_pressed(MouseButton::Left) || pointer_capture.is_captured() {
+        return;
+    }
+    let view_direction = *camera_transform.forward();
+    let Some(start_point) = frame.drag_point(settings.mode, handle, ray, view_direction) else {
+        return;
+    };
+    // Grabbing the handle must not also select entities or move the camera
+    pointer_capture.capture();
+
+    let targets = drag_targets
+        .into_iter()
+        .filter_map(|entity| {
+            let parent = parents
+                .get(entity)
+                .ok()
+                .and_then(|parent| global_transforms.get(parent.get()).ok())
+                .copied()
+                .unwrap_or(GlobalTransform::IDENTITY);
+            Some(DragTarget {
+                entity,
+                start: *transforms.get(entity).ok()?,
+                parent,
+            })
+        })
+        .collect();
+    interaction.drag = Some(GizmoDrag {
+        camera,
+        handle,
+        mode: settings.mode,
+        frame,
+        view_direction,
+        start_point,
+        targets,
+    });
+}
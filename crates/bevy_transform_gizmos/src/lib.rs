@@ -1 +1,278 @@
 //! Gizmos used for the user interface to manipulate transforms.
+//!
+//! The gizmo is shown on the primary entity of the [`EditorSelection`] and moves, rotates or scales
+//! every selected entity that isn't [`Locked`]. Only the cameras rendering [`GIZMO_RENDER_LAYER`]
+//! show it, and it can be dragged from those cameras. It is drawn once for each of these cameras,
+//! on its own [`gizmo_view_layer`], so that it has the same size in every view.
+//!
+//! Keyboard shortcuts:
+//! - W, E and R switch between the translate, rotate and scale modes.
+//! - X toggles between world and local space.
+//! - Holding Ctrl while dragging inverts [`TransformGizmoSettings::snapping`].
+//!
+//! The W, E, R and X shortcuts are ignored while a text input has the [`TextInputFocus`].
+
+use std::f32::consts::PI;
+
+use bevy::{
+    gizmos::config::GizmoLineConfig, picking::PickSet, prelude::*, render::view::RenderLayers,
+};
+use bevy_editor_cam::input::default_camera_inputs;
+use bevy_editor_core::{EditorCorePlugin, EditorSelection, Locked, TextInputFocus};
+use bevy_undo::{ComponentChange, NewChange, UndoPlugin};
+
+mod draw;
+mod frame;
+mod interaction;
+
+pub use interaction::GizmoInteraction;
+
+/// The render layer the transform gizmos are drawn on.
+///
+/// Add it to the [`RenderLayers`] of a camera to show the gizmos and allow dragging them from it.
+pub const GIZMO_RENDER_LAYER: usize = 3;
+
+/// The most cameras the transform gizmo is shown in at the same time.
+///
+/// The cameras rendering [`GIZMO_RENDER_LAYER`] beyond this number don't show the gizmo.
+pub const MAX_GIZMO_VIEWS: usize = 4;
+
+/// The render layer of the transform gizmo sized for the camera of the view `view`.
+///
+/// The plugin adds it to the [`RenderLayers`] of one of the cameras rendering [`GIZMO_RENDER_LAYER`],
+/// the layers from `gizmo_view_layer(0)` to `gizmo_view_layer(MAX_GIZMO_VIEWS - 1)` are reserved.
+pub const fn gizmo_view_layer(view: usize) -> usize {
+    GIZMO_RENDER_LAYER + 1 + view
+}
+
+/// The Bevy Transform Gizmos Plugin.
+pub struct TransformGizmoPlugin;
+
+impl Plugin for TransformGizmoPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EditorCorePlugin>() {
+            app.add_plugins(EditorCorePlugin);
+        }
+        if !app.is_plugin_added::<UndoPlugin>() {
+            app.add_plugins(UndoPlugin);
+        }
+        let gizmo_config = |view| GizmoConfig {
+            line: GizmoLineConfig {
+                width: 3.0,
+                ..default()
+            },
+            // Always draw the gizmo on top of the scene
+            depth_bias: -1.0,
+            render_layers: RenderLayers::layer(gizmo_view_layer(view)),
+            ..default()
+        };
+        // One gizmo for each of the `MAX_GIZMO_VIEWS` views
+        app.insert_gizmo_config(TransformGizmoGroup::<0>, gizmo_config(0))
+            .insert_gizmo_config(TransformGizmoGroup::<1>, gizmo_config(1))
+            .insert_gizmo_config(TransformGizmoGroup::<2>, gizmo_config(2))
+            .insert_gizmo_config(TransformGizmoGroup::<3>, gizmo_config(3));
+
+        app.init_resource::<TransformGizmoSettings>()
+            .init_resource::<GizmoInteraction>()
+            .add_event::<TransformGizmoEdit>()
+            .add_systems(
+                PreUpdate,
+                interaction::drag_gizmo
+                    .in_set(TransformGizmoSet)
+                    .after(PickSet::Last)
+                    // Grabbing a handle must not also start moving the camera
+                    .before(default_camera_inputs),
+            )
+            .add_systems(Update, (handle_shortcuts, record_gizmo_edits))
+            .add_systems(
+                PostUpdate,
+                (
+                    assign_gizmo_views,
+                    (
+                        draw::draw_gizmo::<0>,
+                        draw::draw_gizmo::<1>,
+                        draw::draw_gizmo::<2>,
+                        draw::draw_gizmo::<3>,
+                    ),
+                )
+                    .chain()
+                    .after(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+/// The system set grabbing and dragging the gizmo handles.
+///
+/// Systems reacting to mouse presses in the viewports should run after it and ignore the presses
+/// while the [`PointerCapture`](bevy_editor_core::PointerCapture) is captured: grabbing a handle
+/// captures it until the button is released.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TransformGizmoSet;
+
+/// The gizmo config group of the transform gizmo drawn for the view `VIEW`.
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct TransformGizmoGroup<const VIEW: usize>;
+
+/// What dragging the gizmo does to the selected entities.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GizmoMode {
+    /// Move along an axis or a plane.
+    #[default]
+    Translate,
+    /// Rotate around an axis.
+    Rotate,
+    /// Scale along an axis, or uniformly from the center.
+    Scale,
+}
+
+/// The orientation of the gizmo axes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GizmoSpace {
+    /// The axes of the world.
+    #[default]
+    World,
+    /// The axes of the primary selected entity.
+    ///
+    /// The scale mode always works in local space.
+    Local,
+}
+
+/// Settings of the transform gizmos.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct TransformGizmoSettings {
+    /// The current mode of the gizmo.
+    pub mode: GizmoMode,
+    /// The orientation of the gizmo axes.
+    pub space: GizmoSpace,
+    /// Whether changes are rounded to the snapping increments.
+    ///
+    /// Holding Ctrl while dragging inverts this setting.
+    pub snapping: bool,
+    /// Snapping increment of translations, in world units.
+    pub translate_snap: f32,
+    /// Snapping increment of rotations, in radians.
+    pub rotate_snap: f32,
+    /// Snapping increment of scale factors.
+    pub scale_snap: f32,
+    /// Size of the gizmo, as a fraction of the height of the view.
+    pub size: f32,
+}
+
+impl Default for TransformGizmoSettings {
+    fn default() -> Self {
+        Self {
+            mode: GizmoMode::default(),
+            space: GizmoSpace::default(),
+            snapping: false,
+            translate_snap: 0.5,
+            rotate_snap: PI / 12.0,
+            scale_snap: 0.1,
+            size: 0.15,
+        }
+    }
+}
+
+/// Sent for every entity modified by the gizmo once a drag is over.
+///
+/// Each edit is recorded in the undo [`ChangeChain`](bevy_undo::ChangeChain), the edits of a
+/// single drag are undone together.
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct TransformGizmoEdit {
+    /// The modified entity.
+    pub entity: Entity,
+    /// The [`Transform`] of the entity before the drag.
+    pub before: Transform,
+    /// The [`Transform`] of the entity after the drag.
+    pub after: Transform,
+}
+
+fn handle_shortcuts(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    text_input_focus: Res<TextInputFocus>,
+    mut settings: ResMut<TransformGizmoSettings>,
+) {
+    // The keys are typed in the focused text input
+    if text_input_focus.is_focused() {
+        return;
+    }
+    // Leave the modified keys to other shortcuts
+    if keyboard.any_pressed([
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::AltLeft,
+        KeyCode::AltRight,
+        KeyCode::SuperLeft,
+        KeyCode::SuperRight,
+    ]) {
+        return;
+    }
+
+    if keyboard.just_pressed(KeyCode::KeyW) {
+        settings.mode = GizmoMode::Translate;
+    } else if keyboard.just_pressed(KeyCode::KeyE) {
+        settings.mode = GizmoMode::Rotate;
+    } else if keyboard.just_pressed(KeyCode::KeyR) {
+        settings.mode = GizmoMode::Scale;
+    } else if keyboard.just_pressed(KeyCode::KeyX) {
+        settings.space = match settings.space {
+            GizmoSpace::World => GizmoSpace::Local,
+            GizmoSpace::Local => GizmoSpace::World,
+        };
+    }
+}
+
+/// Give each camera showing the gizmo its own [`gizmo_view_layer`].
+fn assign_gizmo_views(mut cameras: Query<(Entity, &mut RenderLayers), With<Camera>>) {
+    let gizmo_layer = RenderLayers::layer(GIZMO_RENDER_LAYER);
+    let mut gizmo_cameras: Vec<_> = cameras
+        .iter_mut()
+        .filter(|(_, layers)| layers.intersects(&gizmo_layer))
+        .collect();
+    // Keep the views stable from one frame to the next
+    gizmo_cameras.sort_by_key(|(entity, _)| *entity);
+
+    for (view, (_, mut layers)) in gizmo_cameras.into_iter().enumerate() {
+        let assigned = (0..MAX_GIZMO_VIEWS).fold(layers.clone(), |assigned, other_view| {
+            if other_view == view {
+                assigned.with(gizmo_view_layer(other_view))
+            } else {
+                assigned.without(gizmo_view_layer(other_view))
+            }
+        });
+        if *layers != assigned {
+            *layers = assigned;
+        }
+    }
+}
+
+fn record_gizmo_edits(
+    mut edits: EventReader<TransformGizmoEdit>,
+    mut changes: EventWriter<NewChange>,
+) {
+    for edit in edits.read() {
+        changes.send(NewChange::new(ComponentChange::new(
+            edit.entity,
+            edit.before,
+            edit.after,
+        )));
+    }
+}
+
+/// The selected entities the gizmo applies to.
+///
+/// Entities with a selected ancestor are skipped, they already follow their ancestor.
+fn gizmo_targets(
+    selection: &EditorSelection,
+    movable: &Query<(), (With<Transform>, Without<Locked>)>,
+    parents: &Query<&Parent>,
+) -> Vec<Entity> {
+    selection
+        .iter()
+        .filter(|entity| {
+            movable.contains(*entity)
+                && !parents
+                    .iter_ancestors(*entity)
+                    .any(|ancestor| selection.contains(ancestor))
+        })
+        .collect()
+}
@@ -57,42 +57,30 @@ fn setup(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
-    cmd.spawn(PbrBundle {
-        mesh: meshes.add(Mesh::from(Cuboid::from_length(2.0))),
-        material: materials.add(StandardMaterial {
+    cmd.spawn((
+        Mesh3d(meshes.add(Cuboid::from_length(2.0))),
+        MeshMaterial3d(materials.add(StandardMaterial {
             base_color: Color::srgb(0.3, 0.5, 0.3),
             ..default()
-        }),
-        ..default()
-    })
+        })),
+    ))
     .insert(Controller)
     .insert(UndoMarker) //Only entities with this marker will be able to undo
     .insert(OneFrameUndoIgnore::default()); // To prevent adding "Transform add" change in change chain
 
-    cmd.spawn(Camera3dBundle {
-        transform: Transform::from_xyz(0.0, 5.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
-        ..default()
-    });
-
     cmd.spawn((
-        Node::default(),
-        Style {
-            width: Val::Percent(100.0),
-            height: Val::Percent(100.0),
-            justify_content: JustifyContent::Start,
-            align_items: AlignItems::Start,
-            ..default()
-        },
-    ))
-    .with_children(|parent| {
-        parent.spawn(TextBundle {
-            text: Text {
-                sections: vec![],
-                ..default()
-            },
-            ..default()
-        });
-    });
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 5.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    cmd.spawn(Node {
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        justify_content: JustifyContent::Start,
+        align_items: AlignItems::Start,
+        ..default()
+    })
+    .with_child(Text::default());
 }
 
 fn move_cube(
@@ -103,13 +91,13 @@ fn move_cube(
     let speed = 10.0;
     if inputs.pressed(KeyCode::KeyA) {
         for mut transform in &mut query {
-            transform.translation += Vec3::new(-1.0, 0.0, 0.0) * time.delta_seconds() * speed;
+            transform.translation += Vec3::new(-1.0, 0.0, 0.0) * time.delta_secs() * speed;
         }
     }
 
     if inputs.pressed(KeyCode::KeyD) {
         for mut transform in &mut query {
-            transform.translation += Vec3::new(1.0, 0.0, 0.0) * time.delta_seconds() * speed;
+            transform.translation += Vec3::new(1.0, 0.0, 0.0) * time.delta_secs() * speed;
         }
     }
 }
@@ -135,16 +123,9 @@ fn write_undo_text(
     change_chain: Res<ChangeChain>, //Change chain in UndoPlugin
 ) {
     for mut text in &mut query {
-        text.sections.clear();
-        text.sections.push(TextSection::new(
-            "Registered changes\n",
-            TextStyle::default(),
-        ));
+        text.0 = "Registered changes\n".to_string();
         for change in change_chain.changes.iter() {
-            text.sections.push(TextSection::new(
-                format!("{}\n", change.debug_text()),
-                TextStyle::default(),
-            ));
+            text.0.push_str(&format!("{}\n", change.debug_text()));
         }
     }
 }
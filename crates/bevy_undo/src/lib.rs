@@ -19,7 +19,8 @@
 //! 1. Add the `UndoPlugin` to your app
 //! 2. Use the `auto_undo` or `auto_reflected_undo` methods to enable automatic undo for specific components
 //! 3. Mark entities that should support undo/redo with the `UndoMarker` component
-//! 4. Use `UndoRedo` events to trigger undo and redo operations
+//! 4. Use `UndoRedo` events to trigger undo and redo operations, or add the `UndoRedoKeybindsPlugin`
+//!    to send them from the usual `Ctrl + Z` / `Ctrl + Shift + Z` shortcuts
 //!
 //! # Example
 //!
@@ -205,6 +206,37 @@ fn sync_system<M: Component>(
     }
 }
 
+/// Sends [`UndoRedo`] events from the usual keyboard shortcuts
+///
+/// - `Ctrl + Z`: Undo
+/// - `Ctrl + Shift + Z` or `Ctrl + Y`: Redo
+///
+/// Requires the `ButtonInput<KeyCode>` resource, which is added by Bevy's `InputPlugin`.
+#[derive(Default)]
+pub struct UndoRedoKeybindsPlugin;
+
+impl Plugin for UndoRedoKeybindsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, send_undo_redo_from_keyboard);
+    }
+}
+
+fn send_undo_redo_from_keyboard(
+    inputs: Res<ButtonInput<KeyCode>>,
+    mut events: EventWriter<UndoRedo>,
+) {
+    if !inputs.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    let shift = inputs.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
+    if inputs.just_pressed(KeyCode::KeyZ) && !shift {
+        events.send(UndoRedo::Undo);
+    } else if (inputs.just_pressed(KeyCode::KeyZ) && shift) || inputs.just_pressed(KeyCode::KeyY) {
+        events.send(UndoRedo::Redo);
+    }
+}
+
 /// Defines the set of systems related to undo/redo functionality.
 #[derive(SystemSet, Hash, PartialEq, Eq, Debug, Clone)]
 pub enum UndoSet {
@@ -356,7 +388,7 @@ fn undo_redo_logic(world: &mut World) {
     world.resource_scope::<Events<UndoRedo>, _>(|world, mut events| {
         world.resource_scope::<ChangeChain, _>(|world, mut change_chain| {
             {
-                let mut reader = events.get_reader();
+                let mut reader = events.get_cursor();
                 for event in reader.read(&events) {
                     match event {
                         UndoRedo::Undo => {
@@ -437,6 +469,17 @@ impl ChangeChain {
         }
     }
 
+    /// Record that `entity` was despawned and spawned again as `new_entity` outside of undo / redo,
+    /// so the registered changes of `entity` apply to `new_entity`.
+    pub fn remap_entity(&mut self, entity: Entity, new_entity: Entity) {
+        for remapped in self.entity_remap.values_mut() {
+            if *remapped == entity {
+                *remapped = new_entity;
+            }
+        }
+        self.entity_remap.insert(entity, new_entity);
+    }
+
     /// Update destroyed-entity->new-entity mapping for handling entities links after undo / redo
     fn update_remap(&mut self, result: ChangeResult) {
         match result {
@@ -562,7 +605,7 @@ impl EditorChange for RemovedEntity {
         remap: &HashMap<Entity, Entity>,
     ) -> Result<ChangeResult, String> {
         if let Some(e) = remap.get(&self.entity) {
-            if world.get_entity(*e).is_none() {
+            if !world.entities().contains(*e) {
                 let id = world
                     .spawn_empty()
                     .insert(OneFrameUndoIgnore::default())
@@ -594,6 +637,68 @@ impl EditorChange for RemovedEntity {
     }
 }
 
+/// Represents a change of the parent of an entity.
+///
+/// This struct stores the parent before and after the change, allowing the undo system
+/// to move the entity back into its previous place in the hierarchy.
+/// `None` means the entity has no parent. The entity keeps its global transform when it is moved back.
+pub struct ReparentedEntity {
+    /// The ID of the entity that was moved in the hierarchy.
+    pub entity: Entity,
+    /// The parent of the entity before the change.
+    pub old_parent: Option<Entity>,
+    /// The parent of the entity after the change.
+    pub new_parent: Option<Entity>,
+}
+
+impl EditorChange for ReparentedEntity {
+    fn revert(
+        &self,
+        world: &mut World,
+        entity_remap: &HashMap<Entity, Entity>,
+    ) -> Result<ChangeResult, String> {
+        let e = get_entity_with_remap(self.entity, entity_remap);
+        let old_parent = self
+            .old_parent
+            .map(|parent| get_entity_with_remap(parent, entity_remap));
+        if let Some(parent) = old_parent {
+            if !world.entities().contains(parent) {
+                return Err(format!("Parent entity {} does not exist", parent.index()));
+            }
+        }
+        if !world.entities().contains(e) {
+            return Err(format!("Entity {} does not exist", e.index()));
+        }
+        let mut entity_mut = world.entity_mut(e);
+        match old_parent {
+            Some(parent) => {
+                entity_mut.set_parent_in_place(parent);
+            }
+            None => {
+                entity_mut.remove_parent_in_place();
+            }
+        }
+        world
+            .resource_mut::<UndoIgnoreStorage>()
+            .storage
+            .insert(e, OneFrameUndoIgnore::default());
+        info!("Reverted Reparented Entity: {}", e.index());
+        Ok(ChangeResult::Success)
+    }
+
+    fn debug_text(&self) -> String {
+        format!("Reparented Entity: {}", self.entity.index())
+    }
+
+    fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
+        Arc::new(ReparentedEntity {
+            entity: self.entity,
+            old_parent: self.new_parent,
+            new_parent: self.old_parent,
+        })
+    }
+}
+
 /// Represents an changing a component in an entity.
 ///
 /// This struct stores both the old and new values of a component, as well as
@@ -611,6 +716,17 @@ pub struct ComponentChange<T: Component> {
     entity: Entity,
 }
 
+impl<T: Component> ComponentChange<T> {
+    /// Creates a change of the component of `entity` from `old_value` to `new_value`.
+    pub fn new(entity: Entity, old_value: T, new_value: T) -> Self {
+        Self {
+            old_value,
+            new_value,
+            entity,
+        }
+    }
+}
+
 impl<T: Component + Clone> EditorChange for ComponentChange<T> {
     fn revert(
         &self,
@@ -711,6 +827,79 @@ impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedComponentCh
     }
 }
 
+/// Represents a change in a component whose type is only known at runtime, through reflection.
+///
+/// This is what editors use when they edit the components of an entity without knowing their types,
+/// for example from an inspector. The component is written back with its [`ReflectComponent`].
+pub struct DynamicComponentChange {
+    /// The reflection data of the type of the component.
+    reflect_component: ReflectComponent,
+    /// The previous value of the component before the change.
+    old_value: Box<dyn PartialReflect>,
+    /// The new value of the component after the change.
+    new_value: Box<dyn PartialReflect>,
+    /// The ID of the entity whose component was changed.
+    entity: Entity,
+}
+
+impl DynamicComponentChange {
+    /// Creates a change of the component of `entity` from `old_value` to `new_value`.
+    pub fn new(
+        entity: Entity,
+        reflect_component: ReflectComponent,
+        old_value: Box<dyn PartialReflect>,
+        new_value: Box<dyn PartialReflect>,
+    ) -> Self {
+        Self {
+            reflect_component,
+            old_value,
+            new_value,
+            entity,
+        }
+    }
+}
+
+impl EditorChange for DynamicComponentChange {
+    fn revert(
+        &self,
+        world: &mut World,
+        entity_remap: &HashMap<Entity, Entity>,
+    ) -> Result<ChangeResult, String> {
+        let e = get_entity_with_remap(self.entity, entity_remap);
+        if !world.entities().contains(e) {
+            return Err(format!("Entity {e:?} doesn't exist"));
+        }
+
+        let type_registry = world.resource::<AppTypeRegistry>().clone();
+        let mut entity_mut = world.entity_mut(e);
+        self.reflect_component.apply_or_insert(
+            &mut entity_mut,
+            self.old_value.as_ref(),
+            &type_registry.read(),
+        );
+        entity_mut.insert(OneFrameUndoIgnore::default());
+        info!("Reverted DynamicComponentChange for entity: {}", e.index());
+        Ok(ChangeResult::Success)
+    }
+
+    fn debug_text(&self) -> String {
+        format!(
+            "{} changed for entity {:?}",
+            self.old_value.reflect_short_type_path(),
+            self.entity
+        )
+    }
+
+    fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
+        Arc::new(DynamicComponentChange {
+            reflect_component: self.reflect_component.clone(),
+            old_value: self.new_value.clone_value(),
+            new_value: self.old_value.clone_value(),
+            entity: self.entity,
+        })
+    }
+}
+
 /// Represents a change for adding a component to an entity.
 ///
 /// This struct is used to track the addition of a component to an entity,
@@ -726,6 +915,13 @@ pub struct AddedComponent<T: Component> {
     entity: Entity,
 }
 
+impl<T: Component> AddedComponent<T> {
+    /// Creates a change adding `new_value` to `entity`.
+    pub fn new(entity: Entity, new_value: T) -> Self {
+        Self { new_value, entity }
+    }
+}
+
 impl<T: Component + Clone> EditorChange for AddedComponent<T> {
     fn revert(
         &self,
@@ -734,8 +930,11 @@ impl<T: Component + Clone> EditorChange for AddedComponent<T> {
     ) -> Result<ChangeResult, String> {
         let e = get_entity_with_remap(self.entity, entity_remap);
         let mut add_to_ignore = false;
-        if let Some(mut e) = world.get_entity_mut(e) {
-            e.remove::<T>().insert(OneFrameUndoIgnore::default());
+        if world.entities().contains(e) {
+            world
+                .entity_mut(e)
+                .remove::<T>()
+                .insert(OneFrameUndoIgnore::default());
             add_to_ignore = true;
         }
         if add_to_ignore {
@@ -787,8 +986,11 @@ impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedAddedCompon
         let dst = entity_remap
             .get(&self.entity)
             .map_or(self.entity, |remapped| *remapped);
-        if let Some(mut e) = world.get_entity_mut(dst) {
-            e.remove::<T>().insert(OneFrameUndoIgnore::default());
+        if world.entities().contains(dst) {
+            world
+                .entity_mut(dst)
+                .remove::<T>()
+                .insert(OneFrameUndoIgnore::default());
         }
         world
             .resource_mut::<UndoIgnoreStorage>()
@@ -834,6 +1036,13 @@ pub struct RemovedComponent<T: Component + Clone> {
     entity: Entity,
 }
 
+impl<T: Component + Clone> RemovedComponent<T> {
+    /// Creates a change removing `old_value` from `entity`.
+    pub fn new(entity: Entity, old_value: T) -> Self {
+        Self { old_value, entity }
+    }
+}
+
 impl<T: Component + Clone> EditorChange for RemovedComponent<T> {
     fn revert(
         &self,
@@ -843,7 +1052,7 @@ impl<T: Component + Clone> EditorChange for RemovedComponent<T> {
         let mut remap = vec![];
         let dst = entity_remap.get(&self.entity).map_or_else(
             || {
-                if world.get_entity(self.entity).is_some() {
+                if world.entities().contains(self.entity) {
                     self.entity
                 } else {
                     let id = world.spawn_empty().id();
@@ -900,7 +1109,7 @@ impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedRemovedComp
         let mut remap = vec![];
         let dst = entity_remap.get(&self.entity).map_or_else(
             || {
-                if world.get_entity(self.entity).is_some() {
+                if world.entities().contains(self.entity) {
                     self.entity
                 } else {
                     let id = world.spawn_empty().id();
@@ -1204,7 +1413,7 @@ impl AppAutoUndo for App {
 }
 
 fn apply_for_every_typed_field<D: Reflect>(
-    value: &mut dyn Reflect,
+    value: &mut dyn PartialReflect,
     applyer: &dyn Fn(&mut D),
     max_recursion: i32,
 ) {
@@ -1212,7 +1421,7 @@ fn apply_for_every_typed_field<D: Reflect>(
         return;
     }
 
-    if let Some(v) = value.as_any_mut().downcast_mut::<D>() {
+    if let Some(v) = value.try_downcast_mut::<D>() {
         applyer(v);
     } else {
         match value.reflect_mut() {
@@ -1276,8 +1485,8 @@ fn apply_for_every_typed_field<D: Reflect>(
                     );
                 }
             }
-            bevy::reflect::ReflectMut::Value(_v) => {
-                //do nothing. Value was checked before
+            bevy::reflect::ReflectMut::Set(_) | bevy::reflect::ReflectMut::Opaque(_) => {
+                //do nothing. Set values can't be mutated in place, and opaque values were checked before
             }
         }
     }
@@ -1289,15 +1498,13 @@ fn auto_remap_undo_redo<T: Component + Reflect>(
     mut undoredo_applied: EventReader<UndoRedoApplied<T>>,
 ) {
     for event in undoredo_applied.read() {
-        println!("remapping {:?}", event.entity);
         if let Ok(mut data) = query.get_mut(event.entity) {
-            let reflect = data.as_reflect_mut();
+            let reflect = data.as_partial_reflect_mut();
 
             apply_for_every_typed_field::<Entity>(
                 reflect,
                 &|v| {
                     if let Some(e) = change_chain.entity_remap.get(v) {
-                        println!("remap {:?} to {:?}", v, e);
                         *v = *e;
                     }
                 },
@@ -1538,7 +1745,7 @@ mod tests {
         app.update();
         app.update();
 
-        assert!(app.world_mut().get_entity(test_id).is_some());
+        assert!(app.world().entities().contains(test_id));
 
         app.world_mut().send_event(UndoRedo::Undo);
 
@@ -1550,13 +1757,13 @@ mod tests {
         app.update();
 
         assert!(app.world_mut().get::<Name>(test_id).is_none());
-        assert!(app.world_mut().get_entity(test_id).is_some());
+        assert!(app.world().entities().contains(test_id));
 
         app.world_mut().send_event(UndoRedo::Undo);
         app.update();
         app.update();
 
-        assert!(app.world_mut().get_entity(test_id).is_none());
+        assert!(!app.world().entities().contains(test_id));
     }
 
     #[test]
@@ -1600,11 +1807,96 @@ mod tests {
         app.update();
         app.update();
 
-        assert!(app.world_mut().get_entity(test_id_1).is_none());
-        assert!(app.world_mut().get_entity(test_id_2).is_none());
+        assert!(!app.world().entities().contains(test_id_1));
+        assert!(!app.world().entities().contains(test_id_2));
         assert_eq!(app.world_mut().entities().len(), 2);
 
         let mut query = app.world_mut().query::<&Children>();
         assert!(query.get_single(app.world_mut()).is_ok());
     }
+
+    fn press_keys(app: &mut App, keys: &[KeyCode]) {
+        let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        input.release_all();
+        input.clear();
+        for key in keys {
+            input.press(*key);
+        }
+    }
+
+    #[test]
+    fn test_undo_redo_keybinds() {
+        let mut app = configure_app();
+        app.add_plugins(UndoRedoKeybindsPlugin);
+        app.init_resource::<ButtonInput<KeyCode>>();
+
+        let test_id = app.world_mut().spawn_empty().id();
+        app.world_mut()
+            .send_event(NewChange::new(AddedEntity { entity: test_id }));
+
+        app.update();
+        app.update();
+
+        let entity_count = app.world().entities().len();
+
+        press_keys(&mut app, &[KeyCode::ControlLeft, KeyCode::KeyZ]);
+        app.update();
+
+        assert!(!app.world().entities().contains(test_id));
+        assert_eq!(app.world().entities().len(), entity_count - 1);
+
+        press_keys(
+            &mut app,
+            &[KeyCode::ControlLeft, KeyCode::ShiftLeft, KeyCode::KeyZ],
+        );
+        app.update();
+
+        assert_eq!(app.world().entities().len(), entity_count);
+
+        // Z without Ctrl must not undo
+        press_keys(&mut app, &[KeyCode::KeyZ]);
+        app.update();
+
+        assert_eq!(app.world().entities().len(), entity_count);
+    }
+
+    #[test]
+    fn test_undo_reparent() {
+        let mut app = configure_app();
+        app.add_plugins(HierarchyPlugin);
+
+        let old_parent = app.world_mut().spawn_empty().id();
+        let new_parent = app.world_mut().spawn_empty().id();
+        let child = app.world_mut().spawn_empty().set_parent(old_parent).id();
+
+        app.world_mut().entity_mut(child).set_parent(new_parent);
+        app.world_mut().send_event(NewChange::new(ReparentedEntity {
+            entity: child,
+            old_parent: Some(old_parent),
+            new_parent: Some(new_parent),
+        }));
+
+        app.update();
+        app.update();
+
+        app.world_mut().send_event(UndoRedo::Undo);
+        app.update();
+
+        assert_eq!(
+            app.world().get::<Parent>(child).map(Parent::get),
+            Some(old_parent)
+        );
+        assert!(app
+            .world()
+            .get::<Children>(new_parent)
+            .map_or(true, |children| !children.contains(&child)));
+
+        app.world_mut().send_event(UndoRedo::Redo);
+        app.update();
+
+        assert_eq!(
+            app.world().get::<Parent>(child).map(Parent::get),
+            Some(new_parent)
+        );
+    }
 }